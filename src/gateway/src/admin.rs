@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::task::AbortHandle;
+
+/// Operator-visible description of a live `subscribe_changes` stream.
+#[derive(Clone, Serialize)]
+pub struct SubscriptionInfo {
+    pub id: String,
+    pub subscriber: String,
+    pub repo: String,
+    pub branch: String,
+    pub paths: Vec<String>,
+    pub since: DateTime<Utc>,
+}
+
+struct Entry {
+    info: SubscriptionInfo,
+    abort: AbortHandle,
+}
+
+/// Shared registry of active change subscriptions.
+///
+/// Updated in `subscribe_changes` and on stream drop so the admin API and the
+/// `ACTIVE_SUBSCRIPTIONS` gauge report the same live set. Cheap to clone - the
+/// inner map is shared behind an `Arc`.
+#[derive(Clone)]
+pub struct SubscriptionRegistry {
+    inner: Arc<RwLock<HashMap<String, Entry>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Default for SubscriptionRegistry {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(HashMap::new())),
+            next_id: Arc::new(AtomicU64::new(1)),
+        }
+    }
+}
+
+impl SubscriptionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocate a monotonic subscription id.
+    pub fn allocate_id(&self) -> String {
+        format!("sub-{}", self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Record a new live subscription with the handle used to force-close it.
+    pub async fn register(&self, info: SubscriptionInfo, abort: AbortHandle) {
+        self.inner.write().await.insert(info.id.clone(), Entry { info, abort });
+    }
+
+    /// Drop a subscription from the registry (called when the stream ends).
+    pub async fn deregister(&self, id: &str) {
+        self.inner.write().await.remove(id);
+    }
+
+    /// Snapshot of all live subscriptions.
+    pub async fn list(&self) -> Vec<SubscriptionInfo> {
+        self.inner
+            .read()
+            .await
+            .values()
+            .map(|e| e.info.clone())
+            .collect()
+    }
+
+    /// Force-close a subscription by aborting its watch task. Returns `false`
+    /// when no such subscription exists.
+    pub async fn close(&self, id: &str) -> bool {
+        match self.inner.write().await.remove(id) {
+            Some(entry) => {
+                // Aborting skips the watch task's own cleanup, so adjust the
+                // gauge here to keep it consistent with the live set.
+                entry.abort.abort();
+                crate::metrics::ACTIVE_SUBSCRIPTIONS.dec();
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use crate::auth::Claims;
+use crate::db::Database;
+use crate::grpc::admin_gateway_server::AdminGateway;
+use crate::grpc::*;
+use crate::config::Config;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{error, instrument};
+
+/// gRPC counterpart to the REST admin surface.
+///
+/// Operational introspection and control live in their own service so the data
+/// plane (`MemoryGateway`) and the control plane can be exposed, versioned, and
+/// authorized independently. Both planes share the same [`Database`] and
+/// [`SubscriptionRegistry`](crate::admin::SubscriptionRegistry) so the admin
+/// view and the `ACTIVE_SUBSCRIPTIONS` gauge stay consistent with the live
+/// streams served by `MemoryGateway`.
+pub struct AdminGatewayService {
+    db: Arc<Database>,
+    config: Config,
+    oidc: Arc<crate::oidc::JwksVerifier>,
+    subscriptions: crate::admin::SubscriptionRegistry,
+}
+
+impl AdminGatewayService {
+    pub fn new(
+        db: Arc<Database>,
+        config: Config,
+        oidc: Arc<crate::oidc::JwksVerifier>,
+        subscriptions: crate::admin::SubscriptionRegistry,
+    ) -> Self {
+        Self {
+            db,
+            config,
+            oidc,
+            subscriptions,
+        }
+    }
+
+    /// Extract and validate credentials, then require the `dom.admin` scope.
+    ///
+    /// The credential handling mirrors `MemoryGateway::validate_auth`; the admin
+    /// plane additionally gates every RPC behind `dom.admin` so holding a data
+    /// -plane token is never sufficient to introspect or mutate operator state.
+    async fn authenticate_admin(
+        &self,
+        request: &Request<impl std::fmt::Debug>,
+    ) -> Result<Claims, Status> {
+        let claims = self.validate_auth(request).await?;
+        if !claims.scopes.iter().any(|s| s == "dom.admin") {
+            return Err(Status::permission_denied("Missing required scope: dom.admin"));
+        }
+        Ok(claims)
+    }
+
+    async fn validate_auth(
+        &self,
+        request: &Request<impl std::fmt::Debug>,
+    ) -> Result<Claims, Status> {
+        let header = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| Status::unauthenticated("Missing authorization header"))?;
+
+        if let Some(token) = header.strip_prefix("Bearer ") {
+            if self.oidc.is_configured() {
+                if let Ok(iss) = crate::oidc::unverified_issuer(token) {
+                    if self.oidc.knows_issuer(&iss) {
+                        return self
+                            .oidc
+                            .verify(token)
+                            .await
+                            .map_err(|e| Status::unauthenticated(format!("Invalid token: {}", e)));
+                    }
+                }
+            }
+
+            if let Some(public_key_b64) = &self.config.jwt_public_key {
+                use base64::Engine;
+                let public_key = base64::engine::general_purpose::STANDARD
+                    .decode(public_key_b64.trim())
+                    .map_err(|_| Status::unauthenticated("Invalid configured JWT public key"))?;
+                return crate::auth::validate_token_signed(
+                    token,
+                    &self.config.jwt_signature_mode,
+                    &public_key,
+                )
+                .map_err(|e| Status::unauthenticated(format!("Invalid token: {}", e)));
+            }
+
+            return crate::auth::validate_token(token, &self.config.jwt_secret)
+                .map_err(|e| Status::unauthenticated(format!("Invalid token: {}", e)));
+        }
+
+        if let Some(key) = header.strip_prefix("ApiKey ") {
+            return self.validate_api_key(key).await;
+        }
+
+        Err(Status::unauthenticated("Unsupported authorization scheme"))
+    }
+
+    async fn validate_api_key(&self, key: &str) -> Result<Claims, Status> {
+        use crate::auth::KeyStatus;
+
+        let stored = self
+            .db
+            .find_api_key(key)
+            .await
+            .map_err(|e| Status::internal(format!("Key lookup failed: {}", e)))?;
+
+        let now = chrono::Utc::now().timestamp();
+        match crate::auth::check_key_validity(stored.as_ref(), now) {
+            KeyStatus::Valid => Ok(stored.unwrap().to_claims()),
+            KeyStatus::NotYetValid => Err(Status::unauthenticated("API key not yet valid")),
+            KeyStatus::Expired => Err(Status::unauthenticated("API key expired")),
+            KeyStatus::Revoked => Err(Status::unauthenticated("API key revoked")),
+            KeyStatus::Unknown => Err(Status::unauthenticated("Unknown API key")),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl AdminGateway for AdminGatewayService {
+    #[instrument(skip(self, request))]
+    async fn overview(
+        &self,
+        request: Request<AdminOverviewRequest>,
+    ) -> Result<Response<AdminOverviewResponse>, Status> {
+        self.authenticate_admin(&request).await?;
+
+        let overview = self
+            .db
+            .list_overview()
+            .await
+            .map_err(|e| {
+                error!("Overview fetch failed: {}", e);
+                Status::internal("Failed to fetch overview")
+            })?;
+
+        let entries = overview
+            .into_iter()
+            .map(|o| AdminOverviewEntry {
+                repo: o.repo,
+                branch: o.branch,
+                path: o.path,
+                version: o.version,
+                author: o.author,
+            })
+            .collect();
+
+        Ok(Response::new(AdminOverviewResponse { entries }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn health(
+        &self,
+        request: Request<AdminHealthRequest>,
+    ) -> Result<Response<AdminHealthResponse>, Status> {
+        self.authenticate_admin(&request).await?;
+
+        let ready = self.db.ping().await.is_ok();
+        Ok(Response::new(AdminHealthResponse {
+            ready,
+            mongodb: if ready { "up" } else { "down" }.to_string(),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn list_subscriptions(
+        &self,
+        request: Request<ListSubscriptionsRequest>,
+    ) -> Result<Response<ListSubscriptionsResponse>, Status> {
+        self.authenticate_admin(&request).await?;
+
+        let subscriptions = self
+            .subscriptions
+            .list()
+            .await
+            .into_iter()
+            .map(|s| SubscriptionEntry {
+                id: s.id,
+                subscriber: s.subscriber,
+                repo: s.repo,
+                branch: s.branch,
+                paths: s.paths,
+                since: Some(prost_types::Timestamp::from(s.since)),
+            })
+            .collect();
+
+        Ok(Response::new(ListSubscriptionsResponse { subscriptions }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn close_subscription(
+        &self,
+        request: Request<CloseSubscriptionRequest>,
+    ) -> Result<Response<CloseSubscriptionResponse>, Status> {
+        self.authenticate_admin(&request).await?;
+
+        let req = request.into_inner();
+        if self.subscriptions.close(&req.id).await {
+            Ok(Response::new(CloseSubscriptionResponse { closed: true }))
+        } else {
+            Err(Status::not_found("Unknown subscription"))
+        }
+    }
+}
@@ -12,6 +12,9 @@ pub enum AuthError {
     
     #[error("Missing required scope: {0}")]
     MissingScope(String),
+
+    #[error("Unsupported signature algorithm: {0}")]
+    UnsupportedAlgorithm(String),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -19,9 +22,219 @@ pub struct Claims {
     pub sub: String,           // Subject (user ID)
     pub exp: usize,           // Expiration time
     pub iat: usize,           // Issued at
-    pub scopes: Vec<String>,  // Permission scopes
+    pub scopes: Vec<String>,  // Legacy flat permission scopes
+    #[serde(default)]
+    pub roles: Vec<String>,   // Path-scoped RBAC role names
     pub email: Option<String>,
     pub org: Option<String>,  // Organization
+    #[serde(default)]
+    pub auth_provider: Option<String>,     // Identity provider (iss) that vouched for the token
+    #[serde(default)]
+    pub auth_provider_id: Option<String>,  // Subject as seen by that provider
+}
+
+/// An opaque API key with a scope set, owner, and a validity window.
+///
+/// Keys live in MongoDB so operators can mint short-lived credentials for CI
+/// bots without handing out the JWT signing secret.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    /// Opaque key material presented by the caller.
+    pub key: String,
+    /// Who the key belongs to (e.g. `ci-bot@tekfly`).
+    pub owner: String,
+    /// Who minted the key (an admin subject).
+    pub issuer: String,
+    pub scopes: Vec<String>,
+    /// Path-scoped RBAC roles granted to the key.
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Start of the validity window (unix seconds).
+    pub not_before: i64,
+    /// End of the validity window (unix seconds).
+    pub not_after: i64,
+    pub revoked: bool,
+}
+
+/// Level of access a grant confers on a resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Access {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl Access {
+    /// Whether holding this access satisfies a request needing `required`.
+    pub fn covers(self, required: Access) -> bool {
+        matches!(
+            (self, required),
+            (Access::ReadWrite, _) | (Access::Read, Access::Read) | (Access::Write, Access::Write)
+        )
+    }
+}
+
+/// A single permission grant within a [`Role`].
+///
+/// `repo_pattern` and `branch_pattern` are matched with a `*`-glob (an exact
+/// string matches exactly); `path_prefix` is a byte-prefix over the document
+/// path, so `src/` covers everything beneath it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Grant {
+    pub repo_pattern: String,
+    pub branch_pattern: String,
+    pub path_prefix: String,
+    pub access: Access,
+}
+
+impl Grant {
+    /// Whether this grant authorizes `access` on the target resource.
+    pub fn covers(&self, repo: &str, branch: &str, path: &str, access: Access) -> bool {
+        self.access.covers(access)
+            && glob_match(&self.repo_pattern, repo)
+            && glob_match(&self.branch_pattern, branch)
+            && path.starts_with(&self.path_prefix)
+    }
+}
+
+/// A named role: a set of grants stored in MongoDB keyed by `name`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Role {
+    pub name: String,
+    pub grants: Vec<Grant>,
+}
+
+/// Match `value` against a `*`-glob `pattern`, where `*` matches any run of
+/// characters (including none). Patterns without `*` match exactly.
+pub fn glob_match(pattern: &str, value: &str) -> bool {
+    fn helper(p: &[u8], v: &[u8]) -> bool {
+        match p.first() {
+            None => v.is_empty(),
+            Some(b'*') => helper(&p[1..], v) || (!v.is_empty() && helper(p, &v[1..])),
+            Some(&c) => !v.is_empty() && v[0] == c && helper(&p[1..], &v[1..]),
+        }
+    }
+    helper(pattern.as_bytes(), value.as_bytes())
+}
+
+/// Whether any grant in the set covers the target resource with sufficient
+/// access.
+pub fn grants_cover(
+    grants: &[Grant],
+    repo: &str,
+    branch: &str,
+    path: &str,
+    access: Access,
+) -> bool {
+    grants.iter().any(|g| g.covers(repo, branch, path, access))
+}
+
+/// Expand legacy flat `dom.read`/`dom.write` scopes into equivalent wildcard
+/// grants, so tokens minted before RBAC keep authorizing as they used to.
+pub fn legacy_scope_grants(scopes: &[String]) -> Vec<Grant> {
+    let read = scopes.iter().any(|s| s == "dom.read");
+    let write = scopes.iter().any(|s| s == "dom.write");
+    let access = match (read, write) {
+        (true, true) => Some(Access::ReadWrite),
+        (true, false) => Some(Access::Read),
+        (false, true) => Some(Access::Write),
+        (false, false) => None,
+    };
+    access
+        .map(|access| {
+            vec![Grant {
+                repo_pattern: "*".to_string(),
+                branch_pattern: "*".to_string(),
+                path_prefix: String::new(),
+                access,
+            }]
+        })
+        .unwrap_or_default()
+}
+
+/// Result of checking an API key against the current time and revocation state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyStatus {
+    Valid,
+    NotYetValid,
+    Expired,
+    Revoked,
+    Unknown,
+}
+
+/// Check an API key's validity window and revocation flag at `now` (unix seconds).
+///
+/// Kept free of any MongoDB dependency so it can be unit-tested on its own: a
+/// missing key (`None`) is `Unknown`, a revoked key is `Revoked`, and otherwise
+/// the validity interval is compared against `now`.
+pub fn check_key_validity(key: Option<&ApiKey>, now: i64) -> KeyStatus {
+    match key {
+        None => KeyStatus::Unknown,
+        Some(k) if k.revoked => KeyStatus::Revoked,
+        Some(k) if now < k.not_before => KeyStatus::NotYetValid,
+        Some(k) if now >= k.not_after => KeyStatus::Expired,
+        Some(_) => KeyStatus::Valid,
+    }
+}
+
+impl ApiKey {
+    /// Build the `Claims` an authenticated API key maps to.
+    pub fn to_claims(&self) -> Claims {
+        Claims {
+            sub: self.owner.clone(),
+            exp: self.not_after as usize,
+            iat: self.not_before as usize,
+            scopes: self.scopes.clone(),
+            roles: self.roles.clone(),
+            email: None,
+            org: Some(self.issuer.clone()),
+            auth_provider: None,
+            auth_provider_id: None,
+        }
+    }
+}
+
+/// Claims carried by a signed, time-limited share link (a capability URL).
+///
+/// A share link grants read-only access to exactly one snapshot: the
+/// `repo`/`branch`/`path` it names, optionally pinned to a single `version`,
+/// until `exp`. It is an HMAC-SHA512 token signed with the same `jwt_secret`
+/// as ordinary bearer tokens, so no new key material is involved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareClaims {
+    pub repo: String,
+    pub branch: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<i64>,
+    pub exp: usize,
+}
+
+/// Mint a signed share link for a single snapshot.
+pub fn sign_share_token(claims: &ShareClaims, secret: &str) -> Result<String, AuthError> {
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    let header = Header::new(Algorithm::HS512);
+    encode(&header, claims, &EncodingKey::from_secret(secret.as_bytes()))
+        .map_err(|e| AuthError::ValidationFailed(e.to_string()))
+}
+
+/// Verify a share link's HMAC signature and expiry, returning the single
+/// resource it authorizes.
+pub fn verify_share_token(token: &str, secret: &str) -> Result<ShareClaims, AuthError> {
+    let mut validation = Validation::new(Algorithm::HS512);
+    // A share link expires exactly at `exp`; no clock leeway is granted.
+    validation.leeway = 0;
+
+    let token_data = decode::<ShareClaims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &validation,
+    )
+    .map_err(|e| AuthError::ValidationFailed(e.to_string()))?;
+
+    Ok(token_data.claims)
 }
 
 /// Validate JWT token and extract claims
@@ -38,6 +251,55 @@ pub fn validate_token(token: &str, secret: &str) -> Result<Claims, AuthError> {
     Ok(token_data.claims)
 }
 
+/// Validate a JWT whose signature segment is an asymmetric (classical,
+/// post-quantum, or hybrid) signature over the `header.payload` signing input.
+///
+/// The `mode` selects the provider; `public_key` is the verifying key in the
+/// encoding that provider expects (for `hybrid`, the length-prefixed pair).
+/// Returns a typed error so callers can tell a bad signature from a mode they
+/// don't support.
+pub fn validate_token_signed(
+    token: &str,
+    mode: &str,
+    public_key: &[u8],
+) -> Result<Claims, AuthError> {
+    use base64::Engine;
+
+    let provider = crate::security::signature_provider(mode)
+        .map_err(|e| AuthError::UnsupportedAlgorithm(e.to_string()))?;
+
+    let mut parts = token.splitn(3, '.');
+    let header_b64 = parts.next().ok_or(AuthError::InvalidFormat)?;
+    let payload_b64 = parts.next().ok_or(AuthError::InvalidFormat)?;
+    let signature_b64 = parts.next().ok_or(AuthError::InvalidFormat)?;
+    if parts.next().is_some() {
+        return Err(AuthError::InvalidFormat);
+    }
+
+    let signing_input = format!("{}.{}", header_b64, payload_b64);
+    let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    let signature = b64
+        .decode(signature_b64)
+        .map_err(|_| AuthError::InvalidFormat)?;
+
+    provider
+        .verify(signing_input.as_bytes(), &signature, public_key)
+        .map_err(|e| AuthError::ValidationFailed(e.to_string()))?;
+
+    let payload = b64
+        .decode(payload_b64)
+        .map_err(|_| AuthError::InvalidFormat)?;
+    let claims: Claims =
+        serde_json::from_slice(&payload).map_err(|e| AuthError::ValidationFailed(e.to_string()))?;
+
+    let now = chrono::Utc::now().timestamp() as usize;
+    if claims.exp <= now {
+        return Err(AuthError::ValidationFailed("token expired".to_string()));
+    }
+
+    Ok(claims)
+}
+
 /// Generate a development token for testing
 #[cfg(debug_assertions)]
 pub fn generate_dev_token(secret: &str) -> Result<String, AuthError> {
@@ -48,8 +310,11 @@ pub fn generate_dev_token(secret: &str) -> Result<String, AuthError> {
         exp: (chrono::Utc::now() + chrono::Duration::hours(24)).timestamp() as usize,
         iat: chrono::Utc::now().timestamp() as usize,
         scopes: vec!["dom.read".to_string(), "dom.write".to_string()],
+        roles: Vec::new(),
         email: Some("dev@tekfly.io".to_string()),
         org: Some("tekfly".to_string()),
+        auth_provider: None,
+        auth_provider_id: None,
     };
     
     let header = Header::new(Algorithm::HS512);
@@ -77,4 +342,147 @@ mod tests {
         let result = validate_token("invalid-token", "secret");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_share_token_roundtrip() {
+        let secret = "share-secret";
+        let claims = ShareClaims {
+            repo: "app".to_string(),
+            branch: "main".to_string(),
+            path: "src/index.html".to_string(),
+            version: Some(7),
+            exp: (chrono::Utc::now() + chrono::Duration::minutes(5)).timestamp() as usize,
+        };
+
+        let token = sign_share_token(&claims, secret).unwrap();
+        let verified = verify_share_token(&token, secret).unwrap();
+        assert_eq!(verified.repo, "app");
+        assert_eq!(verified.version, Some(7));
+
+        // A different secret must not verify.
+        assert!(verify_share_token(&token, "other-secret").is_err());
+    }
+
+    #[test]
+    fn test_share_token_expired() {
+        let secret = "share-secret";
+        let claims = ShareClaims {
+            repo: "app".to_string(),
+            branch: "main".to_string(),
+            path: "a".to_string(),
+            version: None,
+            exp: (chrono::Utc::now() - chrono::Duration::minutes(1)).timestamp() as usize,
+        };
+        let token = sign_share_token(&claims, secret).unwrap();
+        assert!(verify_share_token(&token, secret).is_err());
+    }
+
+    #[test]
+    fn test_classical_signed_token_roundtrip_and_tamper() {
+        use base64::Engine;
+        use ring::signature::{Ed25519KeyPair, KeyPair};
+
+        let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let ed = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+
+        let claims = Claims {
+            sub: "svc".to_string(),
+            exp: (chrono::Utc::now() + chrono::Duration::hours(1)).timestamp() as usize,
+            iat: chrono::Utc::now().timestamp() as usize,
+            scopes: vec!["dom.read".to_string()],
+            roles: Vec::new(),
+            email: None,
+            org: None,
+            auth_provider: None,
+            auth_provider_id: None,
+        };
+
+        let header = b64.encode(br#"{"alg":"EdDSA","typ":"JWT"}"#);
+        let payload = b64.encode(serde_json::to_vec(&claims).unwrap());
+        let signing_input = format!("{}.{}", header, payload);
+        let sig = ed.sign(signing_input.as_bytes());
+        let token = format!("{}.{}", signing_input, b64.encode(sig.as_ref()));
+
+        let verified = validate_token_signed(&token, "classical", ed.public_key().as_ref()).unwrap();
+        assert_eq!(verified.sub, "svc");
+
+        // Flipping a payload byte breaks the signature.
+        let mut tampered = token.clone().into_bytes();
+        let idx = header.len() + 2; // second byte of the payload segment
+        tampered[idx] ^= 0x01;
+        let tampered = String::from_utf8(tampered).unwrap();
+        assert!(validate_token_signed(&tampered, "classical", ed.public_key().as_ref()).is_err());
+    }
+
+    #[test]
+    fn test_signed_token_unsupported_mode() {
+        assert!(matches!(
+            validate_token_signed("a.b.c", "rsa", &[]),
+            Err(AuthError::UnsupportedAlgorithm(_))
+        ));
+    }
+
+    fn sample_key() -> ApiKey {
+        ApiKey {
+            key: "opaque".to_string(),
+            owner: "ci-bot".to_string(),
+            issuer: "admin".to_string(),
+            scopes: vec!["dom.read".to_string()],
+            roles: Vec::new(),
+            not_before: 100,
+            not_after: 200,
+            revoked: false,
+        }
+    }
+
+    #[test]
+    fn test_key_validity_window() {
+        let key = sample_key();
+        assert_eq!(check_key_validity(Some(&key), 50), KeyStatus::NotYetValid);
+        assert_eq!(check_key_validity(Some(&key), 150), KeyStatus::Valid);
+        assert_eq!(check_key_validity(Some(&key), 200), KeyStatus::Expired);
+        assert_eq!(check_key_validity(None, 150), KeyStatus::Unknown);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("app", "app"));
+        assert!(!glob_match("app", "api"));
+        assert!(glob_match("feature/*", "feature/login"));
+        assert!(!glob_match("feature/*", "main"));
+        assert!(glob_match("*", "anything"));
+    }
+
+    #[test]
+    fn test_grant_scoping() {
+        let grants = vec![Grant {
+            repo_pattern: "app".to_string(),
+            branch_pattern: "feature/*".to_string(),
+            path_prefix: "src/".to_string(),
+            access: Access::Write,
+        }];
+
+        // Covered: matching repo/branch glob and path prefix with write access.
+        assert!(grants_cover(&grants, "app", "feature/login", "src/main.rs", Access::Write));
+        // Wrong branch, wrong path, and insufficient access are all rejected.
+        assert!(!grants_cover(&grants, "app", "main", "src/main.rs", Access::Write));
+        assert!(!grants_cover(&grants, "app", "feature/login", "docs/readme", Access::Write));
+        assert!(!grants_cover(&grants, "app", "feature/login", "src/main.rs", Access::Read));
+    }
+
+    #[test]
+    fn test_legacy_scopes_expand_to_wildcard() {
+        let grants = legacy_scope_grants(&["dom.read".to_string()]);
+        assert!(grants_cover(&grants, "any", "any", "any/path", Access::Read));
+        assert!(!grants_cover(&grants, "any", "any", "any/path", Access::Write));
+    }
+
+    #[test]
+    fn test_revoked_key() {
+        let mut key = sample_key();
+        key.revoked = true;
+        assert_eq!(check_key_validity(Some(&key), 150), KeyStatus::Revoked);
+    }
 }
\ No newline at end of file
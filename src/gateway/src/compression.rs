@@ -0,0 +1,106 @@
+use crate::error::ServiceError;
+use std::collections::HashMap;
+
+/// Metadata key recording the codec a stored blob was compressed with.
+pub const CODEC_META_KEY: &str = "_codec";
+/// Metadata key recording the original (decompressed) blob length.
+pub const RAW_LEN_META_KEY: &str = "_raw_len";
+
+/// Storage codec for document blobs.
+///
+/// Absent codec metadata on a stored document is treated as [`Codec::None`] so
+/// documents written before compression was enabled keep reading correctly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Codec {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Codec::None => "none",
+            Codec::Gzip => "gzip",
+            Codec::Zstd => "zstd",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Codec {
+        match s {
+            "gzip" => Codec::Gzip,
+            "zstd" => Codec::Zstd,
+            _ => Codec::None,
+        }
+    }
+}
+
+/// Compress `data` with `codec` when it is at least `threshold` bytes.
+///
+/// Returns the (possibly unchanged) bytes and the codec actually applied; a blob
+/// below the threshold, or one that fails to shrink, is stored raw as
+/// [`Codec::None`].
+pub fn compress(data: &[u8], codec: Codec, threshold: usize) -> Result<(Vec<u8>, Codec), ServiceError> {
+    if codec == Codec::None || data.len() < threshold {
+        return Ok((data.to_vec(), Codec::None));
+    }
+
+    let compressed = match codec {
+        Codec::Zstd => zstd::encode_all(data, 3)
+            .map_err(|e| ServiceError::Internal(format!("zstd compression failed: {}", e)))?,
+        Codec::Gzip => {
+            use flate2::write::GzEncoder;
+            use flate2::Compression;
+            use std::io::Write;
+
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder
+                .write_all(data)
+                .map_err(|e| ServiceError::Internal(format!("gzip compression failed: {}", e)))?;
+            encoder
+                .finish()
+                .map_err(|e| ServiceError::Internal(format!("gzip compression failed: {}", e)))?
+        }
+        Codec::None => unreachable!(),
+    };
+
+    // Never store a "compressed" blob that's larger than the original.
+    if compressed.len() < data.len() {
+        Ok((compressed, codec))
+    } else {
+        Ok((data.to_vec(), Codec::None))
+    }
+}
+
+/// Remove the internal codec bookkeeping keys from metadata returned to
+/// clients, who only ever see decompressed plaintext and must not try to
+/// decode it again.
+pub fn strip_codec_metadata(metadata: &mut HashMap<String, String>) {
+    metadata.remove(CODEC_META_KEY);
+    metadata.remove(RAW_LEN_META_KEY);
+}
+
+/// Decompress a stored blob using the codec recorded in `metadata`.
+pub fn decompress(data: &[u8], metadata: &HashMap<String, String>) -> Result<Vec<u8>, ServiceError> {
+    let codec = metadata
+        .get(CODEC_META_KEY)
+        .map(|c| Codec::from_str(c))
+        .unwrap_or(Codec::None);
+
+    match codec {
+        Codec::None => Ok(data.to_vec()),
+        Codec::Zstd => zstd::decode_all(data)
+            .map_err(|e| ServiceError::Internal(format!("zstd decompression failed: {}", e))),
+        Codec::Gzip => {
+            use flate2::read::GzDecoder;
+            use std::io::Read;
+
+            let mut decoder = GzDecoder::new(data);
+            let mut out = Vec::new();
+            decoder
+                .read_to_end(&mut out)
+                .map_err(|e| ServiceError::Internal(format!("gzip decompression failed: {}", e)))?;
+            Ok(out)
+        }
+    }
+}
@@ -1,7 +1,25 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+/// A trusted external OIDC provider whose tokens the gateway will accept.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedIssuer {
+    /// The `iss` claim value to match against incoming tokens.
+    pub issuer: String,
+    /// Where to fetch the provider's JSON Web Key Set.
+    pub jwks_uri: String,
+    /// Audiences (`aud`) accepted for this issuer.
+    pub audiences: Vec<String>,
+}
+
+/// The placeholder JWT secret shipped for local development; refused outright
+/// in production.
+pub const DEFAULT_JWT_SECRET: &str = "development-secret-change-in-production";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+// Any field a config file omits falls back to its default, so a file need only
+// carry the values it overrides.
+#[serde(default)]
 pub struct Config {
     pub host: String,
     pub port: u16,
@@ -13,44 +31,265 @@ pub struct Config {
     pub enable_metrics: bool,
     pub metrics_port: u16,
     pub log_level: String,
+    /// External OIDC providers federated in addition to the static secret.
+    #[serde(default)]
+    pub trusted_issuers: Vec<TrustedIssuer>,
+    /// How often to refresh cached JWKS documents, in seconds.
+    #[serde(default = "default_jwks_refresh_secs")]
+    pub jwks_refresh_secs: u64,
+    /// Storage codec for document blobs: `zstd` (default), `gzip`, or `none`.
+    #[serde(default = "default_compression_codec")]
+    pub compression_codec: String,
+    /// Blobs smaller than this many bytes are stored uncompressed.
+    #[serde(default = "default_compression_threshold")]
+    pub compression_threshold: usize,
+    /// Per-side byte ceiling for the history diff-stat; either side larger than
+    /// this falls back to an all-added/all-deleted count.
+    #[serde(default = "default_max_diff_bytes")]
+    pub max_diff_bytes: usize,
+    /// Per-node write authentication mode: `disabled` (default), `explicit`, or
+    /// `shared_secret`.
+    #[serde(default = "default_node_auth_mode")]
+    pub node_auth_mode: String,
+    /// Base64 Ed25519 public keys trusted to sign writes, for `explicit` mode.
+    #[serde(default)]
+    pub trusted_node_keys: Vec<String>,
+    /// Shared secret every node derives its identity from, for `shared_secret`
+    /// mode.
+    #[serde(default)]
+    pub node_shared_secret: Option<String>,
+    /// How often the field-encryption keyring promotes a fresh current key, in
+    /// seconds. A key older than this is due for rotation.
+    #[serde(default = "default_key_rotation_secs")]
+    pub key_rotation_secs: u64,
+    /// How many retired keys to keep for decryption after rotation; older keys
+    /// are dropped so compromised material ages out.
+    #[serde(default = "default_retired_keys_retained")]
+    pub retired_keys_retained: usize,
+    /// JWT signature scheme for asymmetric tokens: `classical` (Ed25519),
+    /// `pqc` (Dilithium-3), or `hybrid` (both, conservatively combined).
+    #[serde(default = "default_jwt_signature_mode")]
+    pub jwt_signature_mode: String,
+    /// Base64 verifying key for asymmetric JWTs. When set, Bearer tokens are
+    /// verified with `jwt_signature_mode`; otherwise they fall back to the
+    /// symmetric `jwt_secret`. For `hybrid` this is the length-prefixed
+    /// classical-then-post-quantum key pair.
+    #[serde(default)]
+    pub jwt_public_key: Option<String>,
+}
+
+fn default_jwks_refresh_secs() -> u64 {
+    300
+}
+
+fn default_compression_codec() -> String {
+    "zstd".to_string()
+}
+
+fn default_compression_threshold() -> usize {
+    1024
+}
+
+fn default_max_diff_bytes() -> usize {
+    1024 * 1024
+}
+
+fn default_node_auth_mode() -> String {
+    "disabled".to_string()
+}
+
+fn default_key_rotation_secs() -> u64 {
+    86_400
+}
+
+fn default_retired_keys_retained() -> usize {
+    2
+}
+
+fn default_jwt_signature_mode() -> String {
+    "classical".to_string()
 }
 
 impl Config {
-    /// Load configuration from environment variables
+    /// Load configuration from environment variables, over built-in defaults.
     pub fn from_env() -> Result<Self> {
-        Ok(Self {
-            host: std::env::var("GATEWAY_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
-            port: std::env::var("GATEWAY_PORT")
-                .unwrap_or_else(|_| "50051".to_string())
-                .parse()?,
-            mongodb_uri: std::env::var("MONGODB_URI")
-                .unwrap_or_else(|_| "mongodb://localhost:27017".to_string()),
-            mongodb_database: std::env::var("MONGODB_DATABASE")
-                .unwrap_or_else(|_| "virtual_dom".to_string()),
-            jwt_secret: std::env::var("JWT_SECRET")
-                .unwrap_or_else(|_| "development-secret-change-in-production".to_string()),
-            tls_cert_path: std::env::var("TLS_CERT_PATH").ok(),
-            tls_key_path: std::env::var("TLS_KEY_PATH").ok(),
-            enable_metrics: std::env::var("ENABLE_METRICS")
-                .unwrap_or_else(|_| "true".to_string())
-                .parse()?,
-            metrics_port: std::env::var("METRICS_PORT")
-                .unwrap_or_else(|_| "9090".to_string())
-                .parse()?,
-            log_level: std::env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string()),
-        })
+        let mut config = Self::default();
+        config.apply_env_overrides()?;
+        Ok(config)
     }
 
-    /// Validate configuration
-    pub fn validate(&self) -> Result<()> {
-        if self.jwt_secret == "development-secret-change-in-production" {
-            tracing::warn!("Using default JWT secret - change in production!");
+    /// Load configuration from a single TOML or YAML file, chosen by extension.
+    /// Values the file omits keep their defaults.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("reading config file {}: {}", path, e))?;
+        let lower = path.to_ascii_lowercase();
+        let config = if lower.ends_with(".yaml") || lower.ends_with(".yml") {
+            serde_yaml::from_str(&contents)
+                .map_err(|e| anyhow::anyhow!("parsing YAML config {}: {}", path, e))?
+        } else {
+            toml::from_str(&contents)
+                .map_err(|e| anyhow::anyhow!("parsing TOML config {}: {}", path, e))?
+        };
+        Ok(config)
+    }
+
+    /// Layered load with precedence `defaults < file < environment`.
+    ///
+    /// The file path comes from `GATEWAY_CONFIG`; if it is unset or names a
+    /// missing file we fall back to defaults, then environment variables are
+    /// applied on top so operators can override individual keys.
+    pub fn load() -> Result<Self> {
+        let mut config = match std::env::var("GATEWAY_CONFIG") {
+            Ok(path) if std::path::Path::new(&path).exists() => Self::from_file(&path)?,
+            Ok(path) => {
+                tracing::warn!("config file {} not found; using defaults", path);
+                Self::default()
+            }
+            Err(_) => Self::default(),
+        };
+        config.apply_env_overrides()?;
+        Ok(config)
+    }
+
+    /// Overlay environment variables onto the current values. A variable that
+    /// is unset leaves the existing value (from a file or default) untouched.
+    pub fn apply_env_overrides(&mut self) -> Result<()> {
+        if let Ok(v) = std::env::var("GATEWAY_HOST") {
+            self.host = v;
+        }
+        if let Ok(v) = std::env::var("GATEWAY_PORT") {
+            self.port = v.parse()?;
+        }
+        if let Ok(v) = std::env::var("MONGODB_URI") {
+            self.mongodb_uri = v;
+        }
+        if let Ok(v) = std::env::var("MONGODB_DATABASE") {
+            self.mongodb_database = v;
+        }
+        if let Ok(v) = std::env::var("JWT_SECRET") {
+            self.jwt_secret = v;
+        }
+        if let Ok(v) = std::env::var("TLS_CERT_PATH") {
+            self.tls_cert_path = Some(v);
+        }
+        if let Ok(v) = std::env::var("TLS_KEY_PATH") {
+            self.tls_key_path = Some(v);
+        }
+        if let Ok(v) = std::env::var("ENABLE_METRICS") {
+            self.enable_metrics = v.parse()?;
+        }
+        if let Ok(v) = std::env::var("METRICS_PORT") {
+            self.metrics_port = v.parse()?;
+        }
+        if let Ok(v) = std::env::var("LOG_LEVEL") {
+            self.log_level = v;
+        }
+        if let Ok(v) = std::env::var("OIDC_ISSUERS") {
+            if let Ok(issuers) = serde_json::from_str(&v) {
+                self.trusted_issuers = issuers;
+            }
+        }
+        if let Some(v) = std::env::var("JWKS_REFRESH_SECS").ok().and_then(|v| v.parse().ok()) {
+            self.jwks_refresh_secs = v;
+        }
+        if let Ok(v) = std::env::var("COMPRESSION_CODEC") {
+            self.compression_codec = v;
+        }
+        if let Some(v) = std::env::var("COMPRESSION_THRESHOLD").ok().and_then(|v| v.parse().ok()) {
+            self.compression_threshold = v;
+        }
+        if let Some(v) = std::env::var("MAX_DIFF_BYTES").ok().and_then(|v| v.parse().ok()) {
+            self.max_diff_bytes = v;
+        }
+        if let Ok(v) = std::env::var("NODE_AUTH_MODE") {
+            self.node_auth_mode = v;
+        }
+        if let Ok(v) = std::env::var("TRUSTED_NODE_KEYS") {
+            self.trusted_node_keys = v
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+        if let Ok(v) = std::env::var("NODE_SHARED_SECRET") {
+            self.node_shared_secret = Some(v);
         }
+        if let Some(v) = std::env::var("KEY_ROTATION_SECS").ok().and_then(|v| v.parse().ok()) {
+            self.key_rotation_secs = v;
+        }
+        if let Some(v) = std::env::var("RETIRED_KEYS_RETAINED").ok().and_then(|v| v.parse().ok()) {
+            self.retired_keys_retained = v;
+        }
+        if let Ok(v) = std::env::var("JWT_SIGNATURE_MODE") {
+            self.jwt_signature_mode = v;
+        }
+        if let Ok(v) = std::env::var("JWT_PUBLIC_KEY") {
+            self.jwt_public_key = Some(v);
+        }
+        Ok(())
+    }
+
+    /// Whether the deployment is running in production mode (`GATEWAY_ENV`).
+    fn is_production() -> bool {
+        std::env::var("GATEWAY_ENV")
+            .map(|v| v.eq_ignore_ascii_case("production"))
+            .unwrap_or(false)
+    }
+
+    /// Validate configuration, applying production strictness when
+    /// `GATEWAY_ENV=production`.
+    pub fn validate(&self) -> Result<()> {
+        self.validate_for_env(Self::is_production())
+    }
 
+    /// Validate with the production-strictness toggle given explicitly.
+    ///
+    /// In production the default JWT secret is a hard error (not a warning),
+    /// configured TLS files must exist and be readable, and binding the
+    /// wildcard host `0.0.0.0` without TLS is refused.
+    pub fn validate_for_env(&self, production: bool) -> Result<()> {
         if self.tls_cert_path.is_some() != self.tls_key_path.is_some() {
             anyhow::bail!("Both TLS cert and key must be provided");
         }
 
+        match self.node_auth_mode.as_str() {
+            "disabled" => {}
+            "explicit" => {
+                if self.trusted_node_keys.is_empty() {
+                    anyhow::bail!("explicit node auth requires at least one trusted_node_keys entry");
+                }
+            }
+            "shared_secret" | "shared-secret" => {
+                if self.node_shared_secret.as_deref().unwrap_or_default().is_empty() {
+                    anyhow::bail!("shared-secret node auth requires node_shared_secret");
+                }
+            }
+            other => anyhow::bail!("unknown node_auth_mode: {}", other),
+        }
+
+        if production {
+            if self.jwt_secret == DEFAULT_JWT_SECRET {
+                anyhow::bail!("production requires a non-default jwt_secret");
+            }
+            match (&self.tls_cert_path, &self.tls_key_path) {
+                (Some(cert), Some(key)) => {
+                    for path in [cert, key] {
+                        std::fs::File::open(path).map_err(|e| {
+                            anyhow::anyhow!("TLS file {} is not readable: {}", path, e)
+                        })?;
+                    }
+                }
+                _ => {
+                    if self.host == "0.0.0.0" {
+                        anyhow::bail!("refusing to bind 0.0.0.0 in production without TLS");
+                    }
+                }
+            }
+        } else if self.jwt_secret == DEFAULT_JWT_SECRET {
+            tracing::warn!("Using default JWT secret - change in production!");
+        }
+
         Ok(())
     }
 }
@@ -62,12 +301,24 @@ impl Default for Config {
             port: 50051,
             mongodb_uri: "mongodb://localhost:27017".to_string(),
             mongodb_database: "virtual_dom".to_string(),
-            jwt_secret: "development-secret-change-in-production".to_string(),
+            jwt_secret: DEFAULT_JWT_SECRET.to_string(),
             tls_cert_path: None,
             tls_key_path: None,
             enable_metrics: true,
             metrics_port: 9090,
             log_level: "info".to_string(),
+            trusted_issuers: Vec::new(),
+            jwks_refresh_secs: default_jwks_refresh_secs(),
+            compression_codec: default_compression_codec(),
+            compression_threshold: default_compression_threshold(),
+            max_diff_bytes: default_max_diff_bytes(),
+            node_auth_mode: default_node_auth_mode(),
+            trusted_node_keys: Vec::new(),
+            node_shared_secret: None,
+            key_rotation_secs: default_key_rotation_secs(),
+            retired_keys_retained: default_retired_keys_retained(),
+            jwt_signature_mode: default_jwt_signature_mode(),
+            jwt_public_key: None,
         }
     }
 }
@@ -94,4 +345,87 @@ mod tests {
         config.tls_key_path = None;
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_from_file_toml_uses_defaults_for_omitted() {
+        let path = std::env::temp_dir().join("vdg-config-test.toml");
+        std::fs::write(
+            &path,
+            "host = \"127.0.0.1\"\nport = 7000\njwt_signature_mode = \"hybrid\"\n",
+        )
+        .unwrap();
+
+        let config = Config::from_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(config.host, "127.0.0.1");
+        assert_eq!(config.port, 7000);
+        assert_eq!(config.jwt_signature_mode, "hybrid");
+        // Omitted keys fall back to defaults.
+        assert_eq!(config.mongodb_database, "virtual_dom");
+        assert_eq!(config.retired_keys_retained, default_retired_keys_retained());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_from_file_yaml() {
+        let path = std::env::temp_dir().join("vdg-config-test.yaml");
+        std::fs::write(&path, "host: 10.0.0.1\nport: 8123\n").unwrap();
+
+        let config = Config::from_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(config.host, "10.0.0.1");
+        assert_eq!(config.port, 8123);
+        assert_eq!(config.log_level, "info");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_layered_load_file_under_env() {
+        // All env mutation is confined to this single test to avoid racing
+        // other tests that also read the process environment.
+        let path = std::env::temp_dir().join("vdg-config-layer.toml");
+        std::fs::write(&path, "host = \"10.0.0.1\"\nport = 9000\n").unwrap();
+
+        std::env::set_var("GATEWAY_CONFIG", &path);
+        std::env::set_var("GATEWAY_PORT", "9999"); // env overrides the file
+        let config = Config::load().unwrap();
+        assert_eq!(config.host, "10.0.0.1"); // from file
+        assert_eq!(config.port, 9999); // env wins
+        std::env::remove_var("GATEWAY_PORT");
+
+        // Missing file falls back to defaults (plus env, still empty here).
+        std::env::set_var("GATEWAY_CONFIG", "/nonexistent/vdg-config.toml");
+        let config = Config::load().unwrap();
+        assert_eq!(config.host, "0.0.0.0");
+        assert_eq!(config.port, 50051);
+
+        std::env::remove_var("GATEWAY_CONFIG");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_production_validation_failures() {
+        // Default secret is a hard error in production.
+        let mut config = Config::default();
+        assert!(config.validate_for_env(true).is_err());
+
+        // With a real secret but a wildcard bind and no TLS, still refused.
+        config.jwt_secret = "a-real-production-secret".to_string();
+        config.host = "0.0.0.0".to_string();
+        assert!(config.validate_for_env(true).is_err());
+
+        // Binding a specific interface without TLS is allowed.
+        config.host = "127.0.0.1".to_string();
+        assert!(config.validate_for_env(true).is_ok());
+
+        // Configured TLS files that don't exist are rejected.
+        config.host = "0.0.0.0".to_string();
+        config.tls_cert_path = Some("/nonexistent/cert.pem".to_string());
+        config.tls_key_path = Some("/nonexistent/key.pem".to_string());
+        assert!(config.validate_for_env(true).is_err());
+
+        // The same default-secret config only warns outside production.
+        let dev = Config::default();
+        assert!(dev.validate_for_env(false).is_ok());
+    }
 }
\ No newline at end of file
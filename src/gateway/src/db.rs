@@ -24,6 +24,12 @@ impl VectorClock {
         Self { value: 1 }
     }
 
+    /// Construct a clock pinned to a known version, used when rebuilding a
+    /// historical snapshot for a specific version.
+    pub fn at(value: i64) -> Self {
+        Self { value }
+    }
+
     pub fn increment(&mut self) {
         self.value += 1;
     }
@@ -63,10 +69,41 @@ pub struct HistoryEntry {
     pub deletions: i32,
 }
 
+/// A stored point in a document's version chain.
+///
+/// Every write appends one record: a full `snapshot` at version 1 and every
+/// [`SNAPSHOT_INTERVAL`]th version thereafter, and a `diff` (a unified edit
+/// script against the previous version) in between. Reconstructing version `v`
+/// loads the nearest snapshot at or below `v` and replays the intervening
+/// diffs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionRecord {
+    pub repo: String,
+    pub branch: String,
+    pub path: String,
+    pub version: i64,
+    #[serde(rename = "type")]
+    pub doc_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<Vec<u8>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<Vec<crate::diff::EditOp>>,
+    pub author: String,
+    pub timestamp: DateTime<Utc>,
+    pub metadata: HashMap<String, String>,
+}
+
+/// A full snapshot is stored at version 1 and every `SNAPSHOT_INTERVAL`
+/// versions so reconstruction never replays more than this many diffs.
+const SNAPSHOT_INTERVAL: i64 = 16;
+
 /// Database connection and operations
 pub struct Database {
     client: Client,
     database_name: String,
+    codec: crate::compression::Codec,
+    compression_threshold: usize,
+    max_diff_bytes: usize,
 }
 
 impl Database {
@@ -74,9 +111,26 @@ impl Database {
         Self {
             client,
             database_name: "virtual_dom".to_string(),
+            codec: crate::compression::Codec::None,
+            compression_threshold: usize::MAX,
+            max_diff_bytes: usize::MAX,
         }
     }
 
+    /// Enable at-rest blob compression with the codec/threshold from `Config`.
+    pub fn with_compression(mut self, codec: crate::compression::Codec, threshold: usize) -> Self {
+        self.codec = codec;
+        self.compression_threshold = threshold;
+        self
+    }
+
+    /// Set the per-side byte ceiling for the history diff-stat (see
+    /// [`crate::diff::diff_stat`]).
+    pub fn with_max_diff_bytes(mut self, max_diff_bytes: usize) -> Self {
+        self.max_diff_bytes = max_diff_bytes;
+        self
+    }
+
     fn collection(&self) -> Collection<Document> {
         self.client
             .database(&self.database_name)
@@ -89,6 +143,90 @@ impl Database {
             .collection("history")
     }
 
+    fn version_collection(&self) -> Collection<VersionRecord> {
+        self.client
+            .database(&self.database_name)
+            .collection("versions")
+    }
+
+    fn role_collection(&self) -> Collection<crate::auth::Role> {
+        self.client
+            .database(&self.database_name)
+            .collection("roles")
+    }
+
+    /// Load the named RBAC roles, silently skipping any that don't exist.
+    #[instrument(skip(self))]
+    pub async fn load_roles(
+        &self,
+        names: &[String],
+    ) -> Result<Vec<crate::auth::Role>, ServiceError> {
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut cursor = self
+            .role_collection()
+            .find(doc! { "name": doc! { "$in": names } })
+            .await?;
+        let mut roles = Vec::new();
+        while let Some(role) = cursor.try_next().await? {
+            roles.push(role);
+        }
+        Ok(roles)
+    }
+
+    fn api_key_collection(&self) -> Collection<crate::auth::ApiKey> {
+        self.client
+            .database(&self.database_name)
+            .collection("api_keys")
+    }
+
+    /// Look up an API key by its opaque material.
+    #[instrument(skip(self, key))]
+    pub async fn find_api_key(
+        &self,
+        key: &str,
+    ) -> Result<Option<crate::auth::ApiKey>, ServiceError> {
+        Ok(self
+            .api_key_collection()
+            .find_one(doc! { "key": key })
+            .await?)
+    }
+
+    /// Persist a freshly minted API key (admin operation).
+    #[instrument(skip(self, key))]
+    pub async fn issue_api_key(&self, key: &crate::auth::ApiKey) -> Result<(), ServiceError> {
+        self.api_key_collection().insert_one(key).await?;
+        Ok(())
+    }
+
+    /// List all API keys (admin operation).
+    #[instrument(skip(self))]
+    pub async fn list_api_keys(&self) -> Result<Vec<crate::auth::ApiKey>, ServiceError> {
+        let mut cursor = self.api_key_collection().find(doc! {}).await?;
+        let mut keys = Vec::new();
+        while let Some(key) = cursor.try_next().await? {
+            keys.push(key);
+        }
+        Ok(keys)
+    }
+
+    /// Flip the revocation flag on a key, returning `NotFound` if it's unknown.
+    #[instrument(skip(self))]
+    pub async fn revoke_api_key(&self, key: &str) -> Result<(), ServiceError> {
+        let result = self
+            .api_key_collection()
+            .update_one(doc! { "key": key }, doc! { "$set": { "revoked": true } })
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(ServiceError::NotFound);
+        }
+
+        Ok(())
+    }
+
     #[instrument(skip(self, doc))]
     pub async fn write_document(
         &self,
@@ -97,6 +235,29 @@ impl Database {
     ) -> Result<(String, i64), ServiceError> {
         let collection = self.collection();
 
+        // Keep the plaintext content so we can append it to the version chain
+        // after compression has rewritten `doc.blob` in place.
+        let content = doc.blob.clone();
+
+        // Compress the blob at rest, recording the codec and original length so
+        // reads can transparently reverse it. Below-threshold blobs stay raw.
+        let raw_len = doc.blob.len();
+        let (blob, applied) =
+            crate::compression::compress(&doc.blob, self.codec, self.compression_threshold)?;
+        if applied != crate::compression::Codec::None {
+            crate::metrics::COMPRESSION_BYTES_SAVED
+                .inc_by((raw_len.saturating_sub(blob.len())) as u64);
+            doc.metadata.insert(
+                crate::compression::CODEC_META_KEY.to_string(),
+                applied.as_str().to_string(),
+            );
+            doc.metadata.insert(
+                crate::compression::RAW_LEN_META_KEY.to_string(),
+                raw_len.to_string(),
+            );
+            doc.blob = blob;
+        }
+
         // Check for existing document and version
         let filter = doc! {
             "repo": &doc.repo,
@@ -128,8 +289,9 @@ impl Database {
                     .with_options(UpdateOptions::builder().upsert(true).build())
                     .await?;
 
-                // Record in history
-                self.record_history(&doc).await?;
+                // Record in history and append to the version chain
+                self.record_history(&doc, &content).await?;
+                self.record_version(&doc, &content).await?;
 
                 Ok((
                     doc.id.unwrap().to_string(),
@@ -144,9 +306,10 @@ impl Database {
                     .as_object_id()
                     .ok_or_else(|| ServiceError::Internal("Failed to get inserted ID".into()))?;
 
-                // Record in history
+                // Record in history and append to the version chain
                 doc.id = Some(id);
-                self.record_history(&doc).await?;
+                self.record_history(&doc, &content).await?;
+                self.record_version(&doc, &content).await?;
 
                 Ok((id.to_string(), doc.version.value()))
             }
@@ -169,15 +332,35 @@ impl Database {
             "path": path,
         };
 
+        let mut doc = collection
+            .find_one(filter)
+            .await?
+            .ok_or(ServiceError::NotFound)?;
+
+        // A request for an older version is served by replaying the diff chain
+        // onto the nearest snapshot; the latest version is already materialised.
+        // A non-positive version is the proto's unset `int64` default and, like
+        // an explicit request for the current version, means "read latest".
         if let Some(v) = version {
-            // TODO: Implement version-specific retrieval from history
-            // For now, just return latest if version matches
+            if v > 0 && v != doc.version.value() {
+                let record = self.find_version(repo, branch, path, v).await?;
+                // `reconstruct` already replays to plaintext, so drop the codec
+                // bookkeeping the stored record still carries.
+                doc.blob = self.reconstruct(repo, branch, path, v).await?;
+                doc.version = VectorClock::at(v);
+                doc.author = record.author;
+                doc.timestamp = record.timestamp;
+                doc.metadata = record.metadata;
+                crate::compression::strip_codec_metadata(&mut doc.metadata);
+                return Ok(doc);
+            }
         }
 
-        collection
-            .find_one(filter)
-            .await?
-            .ok_or(ServiceError::NotFound)
+        // Transparently decompress so callers always see plaintext bytes, and
+        // drop the codec markers so they don't try to decode the plaintext.
+        doc.blob = crate::compression::decompress(&doc.blob, &doc.metadata)?;
+        crate::compression::strip_codec_metadata(&mut doc.metadata);
+        Ok(doc)
     }
 
     #[instrument(skip(self, tx))]
@@ -187,6 +370,7 @@ impl Database {
         branch: &str,
         paths: Vec<String>,
         from_version: u64,
+        resume_token: Option<Vec<u8>>,
         tx: Sender<Result<ChangeEvent, Status>>,
     ) -> Result<(), ServiceError> {
         let collection = self.collection();
@@ -204,27 +388,61 @@ impl Database {
             match_doc.insert("fullDocument.path", doc! { "$in": paths });
         }
 
-        // Filter by version if specified
+        // Filter by version if specified. A resume token makes this redundant,
+        // but we keep it so a first-time (token-less) client can still pick up
+        // from a known version.
         if from_version > 0 {
             match_doc.insert("fullDocument._v.value", doc! { "$gt": from_version as i64 });
         }
 
         pipeline.push(doc! { "$match": match_doc });
 
-        let options = ChangeStreamOptions::builder()
+        // Resume exactly where a reconnecting client left off. The token is the
+        // opaque MongoDB `_id` resume token we previously emitted; decoding it
+        // or opening against a truncated oplog surfaces as `ResumeTokenExpired`
+        // so the client knows to do a full resync rather than silently skipping
+        // events.
+        let mut options = ChangeStreamOptions::builder()
             .full_document(mongodb::options::FullDocumentType::UpdateLookup)
             .build();
 
-        let mut change_stream = collection
+        if let Some(bytes) = resume_token {
+            let token: mongodb::change_stream::event::ResumeToken =
+                bson::from_slice(&bytes).map_err(|_| ServiceError::ResumeTokenExpired)?;
+            options.resume_after = Some(token);
+        }
+
+        let mut change_stream = match collection
             .watch()
             .with_options(options)
             .pipeline(pipeline)
-            .await?;
+            .await
+        {
+            Ok(stream) => stream,
+            Err(e) if is_oplog_window_lost(&e) => return Err(ServiceError::ResumeTokenExpired),
+            Err(e) => return Err(e.into()),
+        };
 
         while let Some(event) = change_stream.next().await {
             match event {
-                Ok(ChangeStreamEvent { full_document, operation_type, .. }) => {
+                Ok(ChangeStreamEvent { id, full_document, operation_type, .. }) => {
+                    // The per-event resume token travels back to the client so a
+                    // later reconnect can continue from exactly this point.
+                    let resume_token = bson::to_vec(&id).unwrap_or_default();
                     if let Some(doc) = full_document {
+                        // Reverse at-rest compression so subscribers receive the
+                        // same plaintext bytes `read_document` hands out.
+                        let diff = match crate::compression::decompress(&doc.blob, &doc.metadata) {
+                            Ok(diff) => diff,
+                            Err(e) => {
+                                error!("Change stream decompression failed: {}", e);
+                                let _ = tx
+                                    .send(Err(Status::internal("Decompression error")))
+                                    .await;
+                                break;
+                            }
+                        };
+
                         let event_type = match operation_type {
                             mongodb::change_stream::event::OperationType::Insert => 1, // CREATE
                             mongodb::change_stream::event::OperationType::Update => 2, // UPDATE
@@ -237,11 +455,12 @@ impl Database {
                             repo: doc.repo,
                             branch: doc.branch,
                             path: doc.path,
-                            diff: doc.blob,
+                            diff,
                             author: doc.author,
                             version: doc.version.value(),
                             timestamp: Some(prost_types::Timestamp::from(doc.timestamp)),
                             metadata: doc.metadata,
+                            resume_token,
                         };
 
                         if tx.send(Ok(change_event)).await.is_err() {
@@ -296,22 +515,237 @@ impl Database {
         Ok(entries)
     }
 
-    async fn record_history(&self, doc: &Document) -> Result<(), ServiceError> {
+    async fn record_history(&self, doc: &Document, content: &[u8]) -> Result<(), ServiceError> {
         let collection = self.history_collection();
 
+        let (additions, deletions) = self.compute_churn(doc, content).await?;
+
         let history_entry = HistoryEntry {
             id: doc.id.unwrap().to_string(),
             version: doc.version.value(),
             author: doc.author.clone(),
             message: doc.metadata.get("message").cloned().unwrap_or_default(),
             timestamp: doc.timestamp,
-            additions: 0, // TODO: Calculate from diff
-            deletions: 0, // TODO: Calculate from diff
+            additions,
+            deletions,
         };
 
         collection.insert_one(history_entry).await?;
         Ok(())
     }
+
+    /// Line-level churn for this write: the new content diffed against the
+    /// previous version's reconstructed content (or the empty buffer for the
+    /// first version).
+    async fn compute_churn(
+        &self,
+        doc: &Document,
+        content: &[u8],
+    ) -> Result<(i32, i32), ServiceError> {
+        let version = doc.version.value();
+        let previous = if version <= 1 {
+            Vec::new()
+        } else {
+            self.reconstruct(&doc.repo, &doc.branch, &doc.path, version - 1)
+                .await?
+        };
+        Ok(crate::diff::diff_stat(&previous, content, self.max_diff_bytes))
+    }
+
+    /// Append `doc`'s version to the reconstruction chain.
+    ///
+    /// Versions on a snapshot boundary are stored in full; the rest are stored
+    /// as a unified edit script against the immediately preceding version.
+    async fn record_version(&self, doc: &Document, content: &[u8]) -> Result<(), ServiceError> {
+        let version = doc.version.value();
+
+        let (doc_type, snapshot, diff) = if version == 1 || version % SNAPSHOT_INTERVAL == 0 {
+            ("snapshot".to_string(), Some(content.to_vec()), None)
+        } else {
+            let base = self
+                .reconstruct(&doc.repo, &doc.branch, &doc.path, version - 1)
+                .await?;
+            ("diff".to_string(), None, Some(crate::diff::diff(&base, content)))
+        };
+
+        let record = VersionRecord {
+            repo: doc.repo.clone(),
+            branch: doc.branch.clone(),
+            path: doc.path.clone(),
+            version,
+            doc_type,
+            snapshot,
+            diff,
+            author: doc.author.clone(),
+            timestamp: doc.timestamp,
+            metadata: doc.metadata.clone(),
+        };
+
+        self.version_collection().insert_one(record).await?;
+        Ok(())
+    }
+
+    /// Fetch the chain record for an exact version, for its authorship and
+    /// metadata. A missing record is reported as corrupt history.
+    async fn find_version(
+        &self,
+        repo: &str,
+        branch: &str,
+        path: &str,
+        version: i64,
+    ) -> Result<VersionRecord, ServiceError> {
+        self.version_collection()
+            .find_one(doc! {
+                "repo": repo,
+                "branch": branch,
+                "path": path,
+                "version": version,
+            })
+            .await?
+            .ok_or_else(|| {
+                ServiceError::CorruptHistory(format!("no record for version {}", version))
+            })
+    }
+
+    /// Rebuild the document content as it existed at `version` by replaying the
+    /// diff chain from the nearest snapshot at or below it.
+    async fn reconstruct(
+        &self,
+        repo: &str,
+        branch: &str,
+        path: &str,
+        version: i64,
+    ) -> Result<Vec<u8>, ServiceError> {
+        let collection = self.version_collection();
+
+        let snapshot = collection
+            .find_one(doc! {
+                "repo": repo,
+                "branch": branch,
+                "path": path,
+                "type": "snapshot",
+                "version": doc! { "$lte": version },
+            })
+            .with_options(FindOneOptions::builder().sort(doc! { "version": -1 }).build())
+            .await?
+            .ok_or_else(|| {
+                ServiceError::CorruptHistory(format!(
+                    "no snapshot at or below version {}",
+                    version
+                ))
+            })?;
+
+        let snapshot_version = snapshot.version;
+        let mut content = snapshot.snapshot.ok_or_else(|| {
+            ServiceError::CorruptHistory(format!(
+                "snapshot at version {} has no payload",
+                snapshot_version
+            ))
+        })?;
+
+        let options = mongodb::options::FindOptions::builder()
+            .sort(doc! { "version": 1 })
+            .build();
+        let mut cursor = collection
+            .find(doc! {
+                "repo": repo,
+                "branch": branch,
+                "path": path,
+                "type": "diff",
+                "version": doc! { "$gt": snapshot_version, "$lte": version },
+            })
+            .with_options(options)
+            .await?;
+
+        // The chain must be a dense run of versions; a gap means a diff is
+        // missing or corrupt and the result would be silently wrong otherwise.
+        let mut expected = snapshot_version + 1;
+        while let Some(record) = cursor.try_next().await? {
+            if record.version != expected {
+                return Err(ServiceError::CorruptHistory(format!(
+                    "missing diff for version {} in chain",
+                    expected
+                )));
+            }
+            let ops = record.diff.ok_or_else(|| {
+                ServiceError::CorruptHistory(format!(
+                    "diff at version {} has no edit script",
+                    record.version
+                ))
+            })?;
+            content = crate::diff::apply(&content, &ops)?;
+            expected += 1;
+        }
+
+        if expected != version + 1 {
+            return Err(ServiceError::CorruptHistory(format!(
+                "history chain ends at version {} before requested {}",
+                expected - 1,
+                version
+            )));
+        }
+
+        Ok(content)
+    }
+}
+
+/// Aggregated overview of a single repo/branch path for the admin API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoOverview {
+    pub repo: String,
+    pub branch: String,
+    pub path: String,
+    pub version: i64,
+    pub author: String,
+}
+
+impl Database {
+    /// Enumerate every known repo/branch/path with its latest version, for the
+    /// admin introspection API.
+    #[instrument(skip(self))]
+    pub async fn list_overview(&self) -> Result<Vec<RepoOverview>, ServiceError> {
+        let collection = self.collection();
+
+        let options = mongodb::options::FindOptions::builder()
+            .sort(doc! { "repo": 1, "branch": 1, "path": 1 })
+            .build();
+
+        let mut cursor = collection.find(doc! {}).with_options(options).await?;
+        let mut out = Vec::new();
+
+        while let Some(doc) = cursor.try_next().await? {
+            out.push(RepoOverview {
+                repo: doc.repo,
+                branch: doc.branch,
+                path: doc.path,
+                version: doc.version.value(),
+                author: doc.author,
+            });
+        }
+
+        Ok(out)
+    }
+
+    /// Ping MongoDB to report readiness for the admin health endpoint.
+    #[instrument(skip(self))]
+    pub async fn ping(&self) -> Result<(), ServiceError> {
+        self.client
+            .database(&self.database_name)
+            .run_command(doc! { "ping": 1 })
+            .await?;
+        Ok(())
+    }
+}
+
+/// Whether a change-stream open failure means the resume token predates the
+/// retained oplog (`ChangeStreamHistoryLost` / `ChangeStreamFatalError`), i.e.
+/// the stream cannot be resumed without losing events.
+fn is_oplog_window_lost(error: &mongodb::error::Error) -> bool {
+    matches!(
+        *error.kind,
+        mongodb::error::ErrorKind::Command(ref command)
+            if command.code == 286 || command.code == 280
+    )
 }
 
 /// Connect to MongoDB
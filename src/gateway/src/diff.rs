@@ -0,0 +1,254 @@
+use crate::error::ServiceError;
+use serde::{Deserialize, Serialize};
+
+/// A single operation in a unified edit script.
+///
+/// An edit script is applied by walking the base buffer front-to-back: `Copy`
+/// and `Delete` consume bytes from the base at the current cursor, while
+/// `Insert` splices literal bytes into the output without advancing the cursor.
+/// Deletions are therefore explicit rather than implied by gaps between copies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum EditOp {
+    /// Copy the next `len` bytes of the base buffer unchanged.
+    Copy { len: usize },
+    /// Skip the next `len` bytes of the base buffer (a deletion).
+    Delete { len: usize },
+    /// Splice these literal bytes into the output.
+    Insert { bytes: Vec<u8> },
+}
+
+/// Build an edit script that turns `base` into `target`.
+///
+/// This uses a cheap common-prefix/common-suffix factoring: everything that
+/// matches at both ends is copied and the differing middle is expressed as a
+/// single delete followed by an insert. It is not a minimal script, but it is
+/// exact — [`apply`] over the result always reproduces `target`.
+pub fn diff(base: &[u8], target: &[u8]) -> Vec<EditOp> {
+    let prefix = base
+        .iter()
+        .zip(target.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    // Longest common suffix, without overlapping the shared prefix on either side.
+    let max_suffix = base.len().min(target.len()) - prefix;
+    let suffix = base
+        .iter()
+        .rev()
+        .zip(target.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut ops = Vec::new();
+    if prefix > 0 {
+        ops.push(EditOp::Copy { len: prefix });
+    }
+    let base_middle = base.len() - prefix - suffix;
+    if base_middle > 0 {
+        ops.push(EditOp::Delete { len: base_middle });
+    }
+    let inserted = &target[prefix..target.len() - suffix];
+    if !inserted.is_empty() {
+        ops.push(EditOp::Insert {
+            bytes: inserted.to_vec(),
+        });
+    }
+    if suffix > 0 {
+        ops.push(EditOp::Copy { len: suffix });
+    }
+    ops
+}
+
+/// Replay an edit script over `base`, returning the reconstructed buffer.
+///
+/// Any op that reads past the end of the base buffer means the script does not
+/// match the base it was recorded against, which is surfaced as
+/// [`ServiceError::CorruptHistory`] rather than panicking or truncating.
+pub fn apply(base: &[u8], ops: &[EditOp]) -> Result<Vec<u8>, ServiceError> {
+    let mut out = Vec::new();
+    let mut cursor = 0usize;
+
+    for op in ops {
+        match op {
+            EditOp::Copy { len } => {
+                let end = cursor
+                    .checked_add(*len)
+                    .filter(|end| *end <= base.len())
+                    .ok_or_else(|| {
+                        ServiceError::CorruptHistory(format!(
+                            "copy of {} bytes at offset {} exceeds base length {}",
+                            len,
+                            cursor,
+                            base.len()
+                        ))
+                    })?;
+                out.extend_from_slice(&base[cursor..end]);
+                cursor = end;
+            }
+            EditOp::Delete { len } => {
+                cursor = cursor
+                    .checked_add(*len)
+                    .filter(|end| *end <= base.len())
+                    .ok_or_else(|| {
+                        ServiceError::CorruptHistory(format!(
+                            "delete of {} bytes at offset {} exceeds base length {}",
+                            len,
+                            cursor,
+                            base.len()
+                        ))
+                    })?;
+            }
+            EditOp::Insert { bytes } => out.extend_from_slice(bytes),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Count a buffer's newline-separated lines, treating empty input as zero.
+fn line_count(data: &[u8]) -> usize {
+    if data.is_empty() {
+        0
+    } else {
+        data.split(|&b| b == b'\n').count()
+    }
+}
+
+/// Line-oriented churn between `old` and `new` as `(additions, deletions)`.
+///
+/// The counts come from the shortest line edit script found with Myers' O(ND)
+/// algorithm. When either side exceeds `max_bytes` the diff is skipped and the
+/// change is reported as every new line added and every old line deleted, so a
+/// pathological input can't blow up the write path.
+pub fn diff_stat(old: &[u8], new: &[u8], max_bytes: usize) -> (i32, i32) {
+    if old.len() > max_bytes || new.len() > max_bytes {
+        return (line_count(new) as i32, line_count(old) as i32);
+    }
+
+    let a: Vec<&[u8]> = old.split(|&b| b == b'\n').collect();
+    let b: Vec<&[u8]> = new.split(|&b| b == b'\n').collect();
+    myers(&a, &b)
+}
+
+/// Myers O(ND) diff over two line sequences, returning `(additions, deletions)`.
+///
+/// The forward pass records the furthest-reaching `x` on each diagonal
+/// `k = x - y` for every edit distance `d`; backtracking the saved traces
+/// attributes each non-diagonal step to an insertion (down) or deletion
+/// (right).
+fn myers(a: &[&[u8]], b: &[&[u8]]) -> (i32, i32) {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = (n + m) as usize;
+    let offset = max as isize;
+
+    let mut v = vec![0isize; 2 * max + 1];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    'search: for d in 0..=max as isize {
+        trace.push(v.clone());
+        let mut k = -d;
+        while k <= d {
+            let mut x = if k == -d
+                || (k != d && v[(k - 1 + offset) as usize] < v[(k + 1 + offset) as usize])
+            {
+                v[(k + 1 + offset) as usize]
+            } else {
+                v[(k - 1 + offset) as usize] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[(k + offset) as usize] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+            k += 2;
+        }
+    }
+
+    let mut additions = 0;
+    let mut deletions = 0;
+    let mut x = n;
+    let mut y = m;
+    for d in (0..trace.len()).rev() {
+        let v = &trace[d];
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d
+            || (k != d && v[(k - 1 + offset) as usize] < v[(k + 1 + offset) as usize])
+        {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[(prev_k + offset) as usize];
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                additions += 1;
+            } else {
+                deletions += 1;
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+
+    (additions, deletions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(base: &[u8], target: &[u8]) {
+        let ops = diff(base, target);
+        assert_eq!(apply(base, &ops).unwrap(), target);
+    }
+
+    #[test]
+    fn test_diff_roundtrip() {
+        roundtrip(b"hello world", b"hello brave world");
+        roundtrip(b"", b"fresh");
+        roundtrip(b"gone", b"");
+        roundtrip(b"unchanged", b"unchanged");
+        roundtrip(b"prefix-mid-suffix", b"prefix-NEW-suffix");
+    }
+
+    #[test]
+    fn test_diff_stat_counts_line_churn() {
+        let old = b"a\nb\nc\n";
+        let new = b"a\nB\nc\nd\n";
+        // Line "b" replaced (1 del + 1 add) and "d" appended before the final
+        // empty trailing line... the net is 2 additions, 1 deletion.
+        let (add, del) = diff_stat(old, new, 1 << 20);
+        assert_eq!((add, del), (2, 1));
+    }
+
+    #[test]
+    fn test_diff_stat_falls_back_when_oversized() {
+        let old = b"one\ntwo\n";
+        let new = b"one\ntwo\nthree\n";
+        let (add, del) = diff_stat(old, new, 1);
+        assert_eq!((add, del), (line_count(new) as i32, line_count(old) as i32));
+    }
+
+    #[test]
+    fn test_apply_rejects_corrupt_script() {
+        let ops = vec![EditOp::Copy { len: 99 }];
+        assert!(matches!(
+            apply(b"short", &ops),
+            Err(ServiceError::CorruptHistory(_))
+        ));
+    }
+}
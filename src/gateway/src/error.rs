@@ -14,6 +14,12 @@ pub enum ServiceError {
     #[error("Version conflict: current version is {current}")]
     VersionConflict { current: i64 },
 
+    #[error("Corrupt history: {0}")]
+    CorruptHistory(String),
+
+    #[error("Resume token is no longer in the oplog window; a full resync is required")]
+    ResumeTokenExpired,
+
     #[error("Authentication failed: {0}")]
     AuthenticationFailed(String),
 
@@ -42,6 +48,12 @@ impl From<ServiceError> for tonic::Status {
             ServiceError::VersionConflict { current } => {
                 tonic::Status::aborted(format!("Version conflict: current version is {}", current))
             }
+            ServiceError::CorruptHistory(msg) => {
+                tonic::Status::data_loss(format!("Corrupt history: {}", msg))
+            }
+            ServiceError::ResumeTokenExpired => tonic::Status::failed_precondition(
+                "Resume token is no longer in the oplog window; resync via read_snapshot",
+            ),
             ServiceError::AuthenticationFailed(msg) => {
                 tonic::Status::unauthenticated(msg)
             }
@@ -4,12 +4,15 @@ use tonic::transport::Server;
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+mod admin;
 mod auth;
+mod compression;
 mod config;
 mod db;
 mod error;
 mod grpc;
 mod metrics;
+mod oidc;
 mod security;
 mod service;
 
@@ -42,7 +45,8 @@ async fn main() -> Result<()> {
     let _metrics_registry = metrics::init();
     
     // Create service
-    let service = MemoryGatewayService::new(db_client, config.clone());
+    let subscriptions = admin::SubscriptionRegistry::new();
+    let service = MemoryGatewayService::new(db_client, config.clone(), subscriptions);
     
     // Create gRPC server
     let addr: SocketAddr = format!("{}:{}", config.host, config.port).parse()?;
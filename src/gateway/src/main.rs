@@ -4,17 +4,24 @@ use tonic::transport::Server;
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+mod admin;
+mod admin_service;
 mod auth;
+mod compression;
 mod config;
 mod db;
+mod diff;
 mod error;
 mod grpc;
 mod metrics;
+mod oidc;
 mod rest;
 mod security;
 mod service;
 
+use crate::admin_service::AdminGatewayService;
 use crate::config::Config;
+use crate::grpc::admin_gateway_server::AdminGatewayServer;
 use crate::grpc::memory_gateway_server::MemoryGatewayServer;
 use crate::service::MemoryGatewayService;
 
@@ -31,8 +38,10 @@ async fn main() -> Result<()> {
 
     info!("Starting Tekfly Virtual-DOM Gateway - Divine Hybrid Architecture");
 
-    // Load configuration
-    let config = Config::from_env()?;
+    // Load configuration (defaults < file < environment) and enforce it before
+    // we bind anything; production mode hard-fails on unsafe settings.
+    let config = Config::load()?;
+    config.validate()?;
     info!("Configuration loaded");
 
     // Initialize MongoDB connection
@@ -43,15 +52,61 @@ async fn main() -> Result<()> {
     let metrics_registry = metrics::init();
     
     // Create shared database instance
-    let db = std::sync::Arc::new(crate::db::Database::new(db_client.clone()));
+    let db = std::sync::Arc::new(
+        crate::db::Database::new(db_client.clone())
+            .with_compression(
+                crate::compression::Codec::from_str(&config.compression_codec),
+                config.compression_threshold,
+            )
+            .with_max_diff_bytes(config.max_diff_bytes),
+    );
     
+    // Shared subscription registry so gRPC and the admin API see the same streams
+    let subscriptions = admin::SubscriptionRegistry::new();
+
+    // Shared node-write authenticator so gRPC, REST, and the admin reload
+    // endpoint all see the same trusted-key set.
+    let node_auth = std::sync::Arc::new(
+        security::NodeAuthenticator::from_config(&config)
+            .expect("Failed to initialize node authenticator"),
+    );
+
+    // Shared OIDC verifier so every surface federates to the same providers.
+    let oidc = std::sync::Arc::new(oidc::JwksVerifier::new(&config));
+
+    // Field-encryption keyring. A background task promotes a fresh current key
+    // once the active one exceeds its age or record budget, so the rotation and
+    // retention knobs in `Config` take effect without operator intervention.
+    let keyring = std::sync::Arc::new(
+        security::Keyring::from_config(&config).expect("Failed to initialize keyring"),
+    );
+    let rotation_interval = std::time::Duration::from_secs(config.key_rotation_secs.clamp(1, 60));
+    let rotation_handle = keyring.spawn_rotation(rotation_interval);
+
     // Create gRPC service
-    let grpc_service = MemoryGatewayService::new(db_client, config.clone());
-    
+    let grpc_service = MemoryGatewayService::new(
+        db_client,
+        config.clone(),
+        subscriptions.clone(),
+        node_auth.clone(),
+    );
+
+    // Create the admin gRPC service, sharing the same registry and database so
+    // the control plane and the data plane observe one live subscription set.
+    let admin_service = AdminGatewayService::new(
+        db.clone(),
+        config.clone(),
+        oidc.clone(),
+        subscriptions.clone(),
+    );
+
     // Create REST app state
     let rest_state = std::sync::Arc::new(rest::AppState {
         db: db.clone(),
         config: config.clone(),
+        oidc,
+        subscriptions,
+        node_auth,
     });
     
     // Spawn gRPC server
@@ -68,6 +123,7 @@ async fn main() -> Result<()> {
             .tls_config(tls_config)
             .expect("Failed to configure TLS")
             .add_service(MemoryGatewayServer::new(grpc_service))
+            .add_service(AdminGatewayServer::new(admin_service))
             .serve(grpc_addr)
             .await
             .expect("gRPC server failed")
@@ -100,7 +156,7 @@ async fn main() -> Result<()> {
     info!("üì° gRPC: port {}, REST: port {}, Metrics: port {}", config.port, rest_port, metrics_port);
     
     // Wait for all servers
-    let _ = tokio::join!(grpc_handle, rest_handle, metrics_handle);
+    let _ = tokio::join!(grpc_handle, rest_handle, metrics_handle, rotation_handle);
 
     Ok(())
 }
@@ -1,82 +1,94 @@
-use prometheus::{Encoder, IntCounter, IntGauge, Registry, TextEncoder};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
 use std::sync::Arc;
 use tracing::info;
 
+/// Request-duration buckets spanning ~1ms to 10s so dashboards can read
+/// p50/p95/p99 for the fast in-cache path and the slow MongoDB tail alike.
+const DURATION_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
 lazy_static::lazy_static! {
-    pub static ref WRITE_REQUESTS: IntCounter = IntCounter::new("virtualdom_write_requests_total", "Total write requests")
-        .expect("metric can be created");
-    
-    pub static ref WRITE_SUCCESS: IntCounter = IntCounter::new("virtualdom_write_success_total", "Successful writes")
-        .expect("metric can be created");
-    
-    pub static ref WRITE_CONFLICTS: IntCounter = IntCounter::new("virtualdom_write_conflicts_total", "Write conflicts")
-        .expect("metric can be created");
-    
-    pub static ref WRITE_ERRORS: IntCounter = IntCounter::new("virtualdom_write_errors_total", "Write errors")
-        .expect("metric can be created");
-    
-    pub static ref READ_REQUESTS: IntCounter = IntCounter::new("virtualdom_read_requests_total", "Total read requests")
-        .expect("metric can be created");
-    
-    pub static ref READ_SUCCESS: IntCounter = IntCounter::new("virtualdom_read_success_total", "Successful reads")
-        .expect("metric can be created");
-    
-    pub static ref READ_NOT_FOUND: IntCounter = IntCounter::new("virtualdom_read_not_found_total", "Read not found")
-        .expect("metric can be created");
-    
-    pub static ref READ_ERRORS: IntCounter = IntCounter::new("virtualdom_read_errors_total", "Read errors")
-        .expect("metric can be created");
-    
-    pub static ref ACTIVE_SUBSCRIPTIONS: IntGauge = IntGauge::new("virtualdom_active_subscriptions", "Active change subscriptions")
-        .expect("metric can be created");
+    /// Write requests sliced by repo/branch and result (success/conflict/forbidden/error).
+    pub static ref WRITE_REQUESTS: IntCounterVec = IntCounterVec::new(
+        Opts::new("virtualdom_write_requests_total", "Total write requests"),
+        &["repo", "branch", "result"],
+    ).expect("metric can be created");
+
+    /// Read requests sliced by repo/branch and result (success/not_found/forbidden/error).
+    pub static ref READ_REQUESTS: IntCounterVec = IntCounterVec::new(
+        Opts::new("virtualdom_read_requests_total", "Total read requests"),
+        &["repo", "branch", "result"],
+    ).expect("metric can be created");
+
+    /// Per-handler request duration, keyed by the handler and repo.
+    pub static ref REQUEST_DURATION: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("virtualdom_request_duration_seconds", "Request duration in seconds")
+            .buckets(DURATION_BUCKETS.to_vec()),
+        &["handler", "repo"],
+    ).expect("metric can be created");
+
+    /// Live change subscriptions, incremented/decremented around the stream lifecycle.
+    pub static ref ACTIVE_SUBSCRIPTIONS: IntGauge = IntGauge::new(
+        "virtualdom_active_subscriptions", "Active change subscriptions",
+    ).expect("metric can be created");
+
+    /// Cumulative bytes saved by at-rest blob compression.
+    pub static ref COMPRESSION_BYTES_SAVED: IntCounter = IntCounter::new(
+        "virtualdom_compression_bytes_saved_total", "Bytes saved by blob compression",
+    ).expect("metric can be created");
+}
+
+/// Record a write outcome for a given repo/branch.
+pub fn record_write(repo: &str, branch: &str, result: &str) {
+    WRITE_REQUESTS.with_label_values(&[repo, branch, result]).inc();
+}
+
+/// Record a read outcome for a given repo/branch.
+pub fn record_read(repo: &str, branch: &str, result: &str) {
+    READ_REQUESTS.with_label_values(&[repo, branch, result]).inc();
+}
+
+/// Start a duration timer for a handler; the returned guard records on drop.
+pub fn start_timer(handler: &str, repo: &str) -> prometheus::HistogramTimer {
+    REQUEST_DURATION
+        .with_label_values(&[handler, repo])
+        .start_timer()
 }
 
 pub fn init() -> Arc<Registry> {
     let registry = Arc::new(Registry::new());
-    
+
     registry
         .register(Box::new(WRITE_REQUESTS.clone()))
         .expect("collector can be registered");
-    
-    registry
-        .register(Box::new(WRITE_SUCCESS.clone()))
-        .expect("collector can be registered");
-    
-    registry
-        .register(Box::new(WRITE_CONFLICTS.clone()))
-        .expect("collector can be registered");
-    
-    registry
-        .register(Box::new(WRITE_ERRORS.clone()))
-        .expect("collector can be registered");
-    
+
     registry
         .register(Box::new(READ_REQUESTS.clone()))
         .expect("collector can be registered");
-    
-    registry
-        .register(Box::new(READ_SUCCESS.clone()))
-        .expect("collector can be registered");
-    
+
     registry
-        .register(Box::new(READ_NOT_FOUND.clone()))
+        .register(Box::new(REQUEST_DURATION.clone()))
         .expect("collector can be registered");
-    
+
     registry
-        .register(Box::new(READ_ERRORS.clone()))
+        .register(Box::new(ACTIVE_SUBSCRIPTIONS.clone()))
         .expect("collector can be registered");
-    
+
     registry
-        .register(Box::new(ACTIVE_SUBSCRIPTIONS.clone()))
+        .register(Box::new(COMPRESSION_BYTES_SAVED.clone()))
         .expect("collector can be registered");
-    
+
     info!("Metrics registry initialized");
     registry
 }
 
 pub async fn serve_metrics(registry: Arc<Registry>, port: u16) {
     use axum::{routing::get, Router};
-    
+
     let app = Router::new().route("/metrics", get(move || {
         let registry = registry.clone();
         async move {
@@ -87,10 +99,10 @@ pub async fn serve_metrics(registry: Arc<Registry>, port: u16) {
             String::from_utf8(buffer).unwrap()
         }
     }));
-    
+
     let addr = format!("0.0.0.0:{}", port);
     info!("Metrics server listening on {}", addr);
-    
+
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     axum::serve(listener, app).await.unwrap();
-}
\ No newline at end of file
+}
@@ -0,0 +1,148 @@
+use crate::auth::{AuthError, Claims};
+use crate::config::{Config, TrustedIssuer};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, DecodingKey, Validation};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+/// A JWKS document cached together with the instant it was fetched.
+struct CachedJwks {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+/// Verifies RS256/ES256 tokens issued by configured external OIDC providers,
+/// selecting the verifier by the token's `iss` claim and the signing key by its
+/// `kid`. JWKS documents are cached and refreshed on a fixed interval.
+pub struct JwksVerifier {
+    refresh: Duration,
+    issuers: HashMap<String, TrustedIssuer>,
+    cache: RwLock<HashMap<String, CachedJwks>>,
+}
+
+impl JwksVerifier {
+    pub fn new(config: &Config) -> Self {
+        let issuers = config
+            .trusted_issuers
+            .iter()
+            .map(|i| (i.issuer.clone(), i.clone()))
+            .collect();
+
+        Self {
+            refresh: Duration::from_secs(config.jwks_refresh_secs),
+            issuers,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Whether any external issuers are configured.
+    pub fn is_configured(&self) -> bool {
+        !self.issuers.is_empty()
+    }
+
+    /// Whether `iss` names one of the trusted external providers.
+    pub fn knows_issuer(&self, iss: &str) -> bool {
+        self.issuers.contains_key(iss)
+    }
+
+    /// Verify a federated token and map the provider identity into `Claims`.
+    pub async fn verify(&self, token: &str) -> Result<Claims, AuthError> {
+        let iss = unverified_issuer(token)?;
+        let issuer = self
+            .issuers
+            .get(&iss)
+            .ok_or_else(|| AuthError::ValidationFailed(format!("Untrusted issuer: {}", iss)))?;
+
+        let header = decode_header(token).map_err(|_| AuthError::InvalidFormat)?;
+        let kid = header.kid.ok_or(AuthError::InvalidFormat)?;
+
+        let jwk = self.key_for(issuer, &kid).await?;
+        let decoding_key =
+            DecodingKey::from_jwk(&jwk).map_err(|e| AuthError::ValidationFailed(e.to_string()))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&issuer.issuer]);
+        validation.set_audience(&issuer.audiences);
+
+        let mut claims = decode::<Claims>(token, &decoding_key, &validation)
+            .map_err(|e| AuthError::ValidationFailed(e.to_string()))?
+            .claims;
+
+        // Record which provider vouched for the subject so documents carry
+        // provenance across providers.
+        claims.auth_provider = Some(issuer.issuer.clone());
+        claims.auth_provider_id = Some(claims.sub.clone());
+
+        Ok(claims)
+    }
+
+    /// Return the signing key matching `kid`, refreshing the cache if the entry
+    /// is stale or the `kid` is absent (handles key rotation at the provider).
+    async fn key_for(
+        &self,
+        issuer: &TrustedIssuer,
+        kid: &str,
+    ) -> Result<jsonwebtoken::jwk::Jwk, AuthError> {
+        {
+            let cache = self.cache.read().await;
+            if let Some(entry) = cache.get(&issuer.issuer) {
+                if entry.fetched_at.elapsed() < self.refresh {
+                    if let Some(jwk) = entry.keys.find(kid) {
+                        return Ok(jwk.clone());
+                    }
+                }
+            }
+        }
+
+        // Cache miss, stale, or unknown kid - refetch.
+        let keys = fetch_jwks(&issuer.jwks_uri).await?;
+        let selected = keys.find(kid).cloned();
+
+        let mut cache = self.cache.write().await;
+        cache.insert(
+            issuer.issuer.clone(),
+            CachedJwks {
+                keys,
+                fetched_at: Instant::now(),
+            },
+        );
+
+        selected.ok_or_else(|| AuthError::ValidationFailed(format!("Unknown signing key: {}", kid)))
+    }
+}
+
+/// Decode (without verifying) the payload of a JWT to read its `iss` claim.
+///
+/// Used only to route a token to the correct verifier; the signature is checked
+/// afterwards against the selected issuer's keys.
+pub fn unverified_issuer(token: &str) -> Result<String, AuthError> {
+    use base64::Engine;
+
+    let payload = token.split('.').nth(1).ok_or(AuthError::InvalidFormat)?;
+    let decoded = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| AuthError::InvalidFormat)?;
+
+    #[derive(serde::Deserialize)]
+    struct IssOnly {
+        iss: String,
+    }
+
+    let iss: IssOnly = serde_json::from_slice(&decoded).map_err(|_| AuthError::InvalidFormat)?;
+    Ok(iss.iss)
+}
+
+async fn fetch_jwks(uri: &str) -> Result<JwkSet, AuthError> {
+    debug!("Fetching JWKS from {}", uri);
+    let response = reqwest::get(uri).await.map_err(|e| {
+        warn!("JWKS fetch failed: {}", e);
+        AuthError::ValidationFailed(format!("JWKS fetch failed: {}", e))
+    })?;
+
+    response
+        .json::<JwkSet>()
+        .await
+        .map_err(|e| AuthError::ValidationFailed(format!("Invalid JWKS document: {}", e)))
+}
@@ -46,6 +46,9 @@ pub struct ReadSnapshotRequest {
     pub branch: String,
     pub path: String,
     pub version: Option<i64>,
+    /// Optional signed share link authorizing this single snapshot in lieu of
+    /// an `Authorization` header.
+    pub sig: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -84,19 +87,148 @@ pub struct GetHistoryResponse {
     pub has_more: bool,
 }
 
-/// Extract and validate JWT from request
-fn validate_auth(headers: &HeaderMap, config: &Config) -> Result<Claims, (StatusCode, String)> {
-    let auth_header = headers
+#[derive(Serialize, Deserialize)]
+pub struct BatchMutateItem {
+    pub repo: String,
+    pub branch: String,
+    pub path: String,
+    pub diff: Vec<u8>,
+    pub parent_version: Option<i64>,
+    pub metadata: Option<std::collections::HashMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BatchMutateRequest {
+    pub items: Vec<BatchMutateItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BatchMutateResult {
+    pub id: String,
+    pub version: i64,
+    pub conflict: Option<ConflictInfo>,
+    /// Set when this item failed or was forbidden; the rest of the batch is
+    /// unaffected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BatchMutateResponse {
+    pub results: Vec<BatchMutateResult>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BatchReadItem {
+    pub repo: String,
+    pub branch: String,
+    pub path: String,
+    pub version: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BatchReadRequest {
+    pub items: Vec<BatchReadItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BatchReadResult {
+    pub found: bool,
+    pub id: String,
+    pub content: Vec<u8>,
+    pub version: i64,
+    pub author: String,
+    pub metadata: std::collections::HashMap<String, String>,
+    /// Set when this item failed or was forbidden; the rest of the batch is
+    /// unaffected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BatchReadResponse {
+    pub results: Vec<BatchReadResult>,
+}
+
+/// Extract and validate credentials from a request.
+///
+/// Accepts either a `Bearer <jwt>` or an `ApiKey <key>` opaque credential
+/// resolved against the MongoDB key store.
+async fn validate_auth(
+    headers: &HeaderMap,
+    state: &AppState,
+) -> Result<Claims, (StatusCode, String)> {
+    let header = headers
         .get("authorization")
         .and_then(|v| v.to_str().ok())
-        .and_then(|v| v.strip_prefix("Bearer "))
         .ok_or_else(|| (StatusCode::UNAUTHORIZED, "Missing authorization header".to_string()))?;
-    
-    crate::auth::validate_token(auth_header, &config.jwt_secret)
-        .map_err(|e| (StatusCode::UNAUTHORIZED, format!("Invalid token: {}", e)))
+
+    if let Some(token) = header.strip_prefix("Bearer ") {
+        if state.oidc.is_configured() {
+            if let Ok(iss) = crate::oidc::unverified_issuer(token) {
+                if state.oidc.knows_issuer(&iss) {
+                    return state.oidc.verify(token).await.map_err(|e| {
+                        (StatusCode::UNAUTHORIZED, format!("Invalid token: {}", e))
+                    });
+                }
+            }
+        }
+
+        // With a configured verifying key, use the asymmetric scheme named by
+        // `jwt_signature_mode`; otherwise fall back to the symmetric secret.
+        if let Some(public_key_b64) = &state.config.jwt_public_key {
+            use base64::Engine;
+            let public_key = base64::engine::general_purpose::STANDARD
+                .decode(public_key_b64.trim())
+                .map_err(|_| {
+                    (StatusCode::INTERNAL_SERVER_ERROR, "Invalid configured JWT public key".to_string())
+                })?;
+            return crate::auth::validate_token_signed(
+                token,
+                &state.config.jwt_signature_mode,
+                &public_key,
+            )
+            .map_err(|e| (StatusCode::UNAUTHORIZED, format!("Invalid token: {}", e)));
+        }
+
+        return crate::auth::validate_token(token, &state.config.jwt_secret)
+            .map_err(|e| (StatusCode::UNAUTHORIZED, format!("Invalid token: {}", e)));
+    }
+
+    if let Some(key) = header.strip_prefix("ApiKey ") {
+        return validate_api_key(key, state).await;
+    }
+
+    Err((StatusCode::UNAUTHORIZED, "Unsupported authorization scheme".to_string()))
 }
 
-/// Check if user has required scope
+/// Resolve an opaque API key to claims with a distinct reason on rejection.
+async fn validate_api_key(
+    key: &str,
+    state: &AppState,
+) -> Result<Claims, (StatusCode, String)> {
+    use crate::auth::KeyStatus;
+
+    let stored = state
+        .db
+        .find_api_key(key)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Key lookup failed: {}", e)))?;
+
+    let now = Utc::now().timestamp();
+    match crate::auth::check_key_validity(stored.as_ref(), now) {
+        KeyStatus::Valid => Ok(stored.unwrap().to_claims()),
+        KeyStatus::NotYetValid => Err((StatusCode::UNAUTHORIZED, "API key not yet valid".to_string())),
+        KeyStatus::Expired => Err((StatusCode::UNAUTHORIZED, "API key expired".to_string())),
+        KeyStatus::Revoked => Err((StatusCode::UNAUTHORIZED, "API key revoked".to_string())),
+        KeyStatus::Unknown => Err((StatusCode::UNAUTHORIZED, "Unknown API key".to_string())),
+    }
+}
+
+/// Check if user has required scope.
+///
+/// Still used for the coarse, global `dom.admin` capability; data-plane access
+/// goes through [`authorize`] instead.
 fn check_scope(claims: &Claims, required: &str) -> Result<(), (StatusCode, String)> {
     if !claims.scopes.contains(&required.to_string()) {
         return Err((StatusCode::FORBIDDEN, format!(
@@ -107,6 +239,133 @@ fn check_scope(claims: &Claims, required: &str) -> Result<(), (StatusCode, Strin
     Ok(())
 }
 
+/// Authorize a request against the caller's path-scoped RBAC roles.
+///
+/// Mirrors the gRPC resolver: the caller's roles (plus any legacy flat scopes)
+/// are expanded to grants and the request is permitted only if one covers the
+/// target repo/branch/path with sufficient access, otherwise `403` names the
+/// uncovered resource.
+async fn authorize(
+    claims: &Claims,
+    state: &AppState,
+    repo: &str,
+    branch: &str,
+    path: &str,
+    access: crate::auth::Access,
+) -> Result<(), (StatusCode, String)> {
+    let roles = state
+        .db
+        .load_roles(&claims.roles)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Role lookup failed: {}", e)))?;
+
+    let mut grants = crate::auth::legacy_scope_grants(&claims.scopes);
+    for role in roles {
+        grants.extend(role.grants);
+    }
+
+    if crate::auth::grants_cover(&grants, repo, branch, path, access) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            format!("No role grants {:?} access to {}/{}/{}", access, repo, branch, path),
+        ))
+    }
+}
+
+/// Verify a share link against the requested snapshot, returning the version
+/// to read.
+///
+/// The signature must cover exactly the requested `repo`/`branch`/`path`; a
+/// pinned version in the signature overrides (and must not conflict with) the
+/// query's `version`. Any mismatch is a `403`, a bad/expired signature a `401`.
+fn authorize_share(
+    sig: &str,
+    query: &ReadSnapshotRequest,
+    state: &AppState,
+) -> Result<Option<i64>, (StatusCode, String)> {
+    let claims = crate::auth::verify_share_token(sig, &state.config.jwt_secret)
+        .map_err(|e| (StatusCode::UNAUTHORIZED, format!("Invalid share link: {}", e)))?;
+
+    if claims.repo != query.repo || claims.branch != query.branch || claims.path != query.path {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Share link does not cover the requested resource".to_string(),
+        ));
+    }
+
+    match (claims.version, query.version) {
+        (Some(pinned), Some(requested)) if pinned != requested => Err((
+            StatusCode::FORBIDDEN,
+            "Share link is pinned to a different version".to_string(),
+        )),
+        (Some(pinned), _) => Ok(Some(pinned)),
+        (None, requested) => Ok(requested),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ShareRequest {
+    pub repo: String,
+    pub branch: String,
+    pub path: String,
+    pub version: Option<i64>,
+    /// Time-to-live of the link in seconds.
+    pub ttl_seconds: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ShareResponse {
+    /// The signed token to pass as `?sig=` on `read_snapshot`.
+    pub sig: String,
+    /// Absolute expiry (unix seconds) for the caller's convenience.
+    pub exp: i64,
+}
+
+/// Mint a signed, time-limited share link for one snapshot (requires `dom.read`
+/// on the target resource).
+pub async fn create_share(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Json(req_body): Json<ShareRequest>,
+) -> impl IntoResponse {
+    let claims = match validate_auth(&headers, &state).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    if let Err(e) = authorize(
+        &claims,
+        &state,
+        &req_body.repo,
+        &req_body.branch,
+        &req_body.path,
+        crate::auth::Access::Read,
+    )
+    .await
+    {
+        return e.into_response();
+    }
+
+    let exp = Utc::now().timestamp() + req_body.ttl_seconds;
+    let share_claims = crate::auth::ShareClaims {
+        repo: req_body.repo,
+        branch: req_body.branch,
+        path: req_body.path,
+        version: req_body.version,
+        exp: exp as usize,
+    };
+
+    match crate::auth::sign_share_token(&share_claims, &state.config.jwt_secret) {
+        Ok(sig) => (StatusCode::OK, Json(ShareResponse { sig, exp })).into_response(),
+        Err(e) => {
+            error!("Failed to sign share link: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to create share link").into_response()
+        }
+    }
+}
+
 /// Write diff endpoint
 pub async fn write_diff(
     headers: HeaderMap,
@@ -114,15 +373,24 @@ pub async fn write_diff(
     Json(req_body): Json<WriteDiffRequest>,
 ) -> impl IntoResponse {
     // Validate authentication
-    let claims = match validate_auth(&headers, &state.config) {
+    let claims = match validate_auth(&headers, &state).await {
         Ok(c) => c,
         Err(e) => return e.into_response(),
     };
     
-    if let Err(e) = check_scope(&claims, "dom.write") {
+    if let Err(e) = authorize(
+        &claims,
+        &state,
+        &req_body.repo,
+        &req_body.branch,
+        &req_body.path,
+        crate::auth::Access::Write,
+    )
+    .await
+    {
         return e.into_response();
     }
-    
+
     // Create document
     let doc = Document {
         id: None,
@@ -186,19 +454,39 @@ pub async fn read_snapshot(
     Query(query): Query<ReadSnapshotRequest>,
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    // Validate authentication
-    let claims = match validate_auth(&headers, &state.config) {
-        Ok(c) => c,
-        Err(e) => return e.into_response(),
+    // A signed share link authorizes exactly one snapshot and stands in for the
+    // `Authorization` header plus RBAC check; otherwise fall back to normal
+    // bearer/API-key auth. In both cases `effective_version` is what we read.
+    let effective_version = if let Some(sig) = &query.sig {
+        match authorize_share(sig, &query, &state) {
+            Ok(version) => version,
+            Err(e) => return e.into_response(),
+        }
+    } else {
+        let claims = match validate_auth(&headers, &state).await {
+            Ok(c) => c,
+            Err(e) => return e.into_response(),
+        };
+
+        if let Err(e) = authorize(
+            &claims,
+            &state,
+            &query.repo,
+            &query.branch,
+            &query.path,
+            crate::auth::Access::Read,
+        )
+        .await
+        {
+            return e.into_response();
+        }
+
+        query.version
     };
-    
-    if let Err(e) = check_scope(&claims, "dom.read") {
-        return e.into_response();
-    }
-    
+
     match state
         .db
-        .read_document(&query.repo, &query.branch, &query.path, query.version)
+        .read_document(&query.repo, &query.branch, &query.path, effective_version)
         .await
     {
         Ok(doc) => {
@@ -228,15 +516,24 @@ pub async fn get_history(
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
     // Validate authentication
-    let claims = match validate_auth(&headers, &state.config) {
+    let claims = match validate_auth(&headers, &state).await {
         Ok(c) => c,
         Err(e) => return e.into_response(),
     };
     
-    if let Err(e) = check_scope(&claims, "dom.read") {
+    if let Err(e) = authorize(
+        &claims,
+        &state,
+        &query.repo,
+        &query.branch,
+        &query.path,
+        crate::auth::Access::Read,
+    )
+    .await
+    {
         return e.into_response();
     }
-    
+
     let limit = query.limit.unwrap_or(20).min(100);
     
     match state
@@ -277,10 +574,426 @@ pub async fn get_history(
     }
 }
 
+/// Batch mutate endpoint - apply many writes in one round trip
+pub async fn batch_mutate(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Json(req_body): Json<BatchMutateRequest>,
+) -> impl IntoResponse {
+    let claims = match validate_auth(&headers, &state).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    let mut results = Vec::with_capacity(req_body.items.len());
+    for item in req_body.items {
+        // A forbidden item is reported in place rather than aborting the batch,
+        // so the client can see which sub-writes landed.
+        if let Err((_, message)) = authorize(
+            &claims,
+            &state,
+            &item.repo,
+            &item.branch,
+            &item.path,
+            crate::auth::Access::Write,
+        )
+        .await
+        {
+            results.push(BatchMutateResult {
+                id: String::new(),
+                version: 0,
+                conflict: None,
+                error: Some(message),
+            });
+            continue;
+        }
+
+        let doc = Document {
+            id: None,
+            repo: item.repo.clone(),
+            branch: item.branch.clone(),
+            path: item.path.clone(),
+            blob: item.diff,
+            author: claims.sub.clone(),
+            version: VectorClock::new(),
+            timestamp: Utc::now(),
+            doc_type: "diff".to_string(),
+            metadata: item.metadata.unwrap_or_default(),
+        };
+
+        let result = match state
+            .db
+            .write_document(doc, item.parent_version.unwrap_or(0))
+            .await
+        {
+            Ok((id, version)) => BatchMutateResult {
+                id,
+                version,
+                conflict: None,
+                error: None,
+            },
+            Err(crate::error::ServiceError::VersionConflict { current }) => {
+                match state
+                    .db
+                    .read_document(&item.repo, &item.branch, &item.path, Some(current))
+                    .await
+                {
+                    Ok(current_doc) => BatchMutateResult {
+                        id: String::new(),
+                        version: 0,
+                        conflict: Some(ConflictInfo {
+                            has_conflict: true,
+                            current_version: current,
+                            current_author: current_doc.author,
+                            current_content: current_doc.blob,
+                        }),
+                        error: None,
+                    },
+                    Err(e) => {
+                        error!("Failed to fetch conflict info: {}", e);
+                        BatchMutateResult {
+                            id: String::new(),
+                            version: 0,
+                            conflict: None,
+                            error: Some(format!("Failed to fetch conflict info: {}", e)),
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                error!("Batch write failed: {}", e);
+                BatchMutateResult {
+                    id: String::new(),
+                    version: 0,
+                    conflict: None,
+                    error: Some("Write operation failed".to_string()),
+                }
+            }
+        };
+
+        results.push(result);
+    }
+
+    (StatusCode::OK, Json(BatchMutateResponse { results })).into_response()
+}
+
+/// Batch read endpoint - fetch many snapshots in one round trip
+pub async fn batch_read(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Json(req_body): Json<BatchReadRequest>,
+) -> impl IntoResponse {
+    let claims = match validate_auth(&headers, &state).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    let mut results = Vec::with_capacity(req_body.items.len());
+    for item in req_body.items {
+        // Report a denied item in place so the rest of the batch still returns.
+        if let Err((_, message)) = authorize(
+            &claims,
+            &state,
+            &item.repo,
+            &item.branch,
+            &item.path,
+            crate::auth::Access::Read,
+        )
+        .await
+        {
+            results.push(BatchReadResult {
+                found: false,
+                id: String::new(),
+                content: Vec::new(),
+                version: 0,
+                author: String::new(),
+                metadata: Default::default(),
+                error: Some(message),
+            });
+            continue;
+        }
+
+        let result = match state
+            .db
+            .read_document(&item.repo, &item.branch, &item.path, item.version)
+            .await
+        {
+            Ok(doc) => BatchReadResult {
+                found: true,
+                id: doc.id.unwrap_or_default().to_string(),
+                content: doc.blob,
+                version: doc.version.value(),
+                author: doc.author,
+                metadata: doc.metadata,
+                error: None,
+            },
+            Err(crate::error::ServiceError::NotFound) => BatchReadResult {
+                found: false,
+                id: String::new(),
+                content: Vec::new(),
+                version: 0,
+                author: String::new(),
+                metadata: Default::default(),
+                error: None,
+            },
+            Err(e) => {
+                error!("Batch read failed: {}", e);
+                BatchReadResult {
+                    found: false,
+                    id: String::new(),
+                    content: Vec::new(),
+                    version: 0,
+                    author: String::new(),
+                    metadata: Default::default(),
+                    error: Some("Read operation failed".to_string()),
+                }
+            }
+        };
+
+        results.push(result);
+    }
+
+    (StatusCode::OK, Json(BatchReadResponse { results })).into_response()
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct IssueApiKeyRequest {
+    pub key: String,
+    pub owner: String,
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    pub not_before: i64,
+    pub not_after: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RevokeApiKeyRequest {
+    pub key: String,
+}
+
+/// Mint a new API key (requires `dom.admin`).
+pub async fn issue_api_key(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Json(req_body): Json<IssueApiKeyRequest>,
+) -> impl IntoResponse {
+    let claims = match validate_auth(&headers, &state).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    if let Err(e) = check_scope(&claims, "dom.admin") {
+        return e.into_response();
+    }
+
+    let key = crate::auth::ApiKey {
+        key: req_body.key,
+        owner: req_body.owner,
+        issuer: claims.sub,
+        scopes: req_body.scopes,
+        roles: req_body.roles,
+        not_before: req_body.not_before,
+        not_after: req_body.not_after,
+        revoked: false,
+    };
+
+    match state.db.issue_api_key(&key).await {
+        Ok(()) => (StatusCode::CREATED, Json(key)).into_response(),
+        Err(e) => {
+            error!("Failed to issue API key: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to issue API key").into_response()
+        }
+    }
+}
+
+/// List all API keys (requires `dom.admin`).
+pub async fn list_api_keys(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let claims = match validate_auth(&headers, &state).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    if let Err(e) = check_scope(&claims, "dom.admin") {
+        return e.into_response();
+    }
+
+    match state.db.list_api_keys().await {
+        Ok(keys) => (StatusCode::OK, Json(keys)).into_response(),
+        Err(e) => {
+            error!("Failed to list API keys: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to list API keys").into_response()
+        }
+    }
+}
+
+/// Revoke an API key (requires `dom.admin`).
+pub async fn revoke_api_key(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Json(req_body): Json<RevokeApiKeyRequest>,
+) -> impl IntoResponse {
+    let claims = match validate_auth(&headers, &state).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    if let Err(e) = check_scope(&claims, "dom.admin") {
+        return e.into_response();
+    }
+
+    match state.db.revoke_api_key(&req_body.key).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(crate::error::ServiceError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Unknown API key").into_response()
+        }
+        Err(e) => {
+            error!("Failed to revoke API key: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to revoke API key").into_response()
+        }
+    }
+}
+
+/// List repos/branches/paths with their latest versions (requires `dom.admin`).
+pub async fn admin_overview(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let claims = match validate_auth(&headers, &state).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    if let Err(e) = check_scope(&claims, "dom.admin") {
+        return e.into_response();
+    }
+
+    match state.db.list_overview().await {
+        Ok(overview) => (StatusCode::OK, Json(overview)).into_response(),
+        Err(e) => {
+            error!("Overview fetch failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch overview").into_response()
+        }
+    }
+}
+
+/// Report readiness, including MongoDB connectivity (requires `dom.admin`).
+pub async fn admin_health(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let claims = match validate_auth(&headers, &state).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    if let Err(e) = check_scope(&claims, "dom.admin") {
+        return e.into_response();
+    }
+
+    let mongo_ok = state.db.ping().await.is_ok();
+    let status = if mongo_ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(serde_json::json!({
+            "ready": mongo_ok,
+            "mongodb": if mongo_ok { "up" } else { "down" },
+        })),
+    )
+        .into_response()
+}
+
+/// Enumerate active change subscriptions (requires `dom.admin`).
+pub async fn admin_list_subscriptions(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let claims = match validate_auth(&headers, &state).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    if let Err(e) = check_scope(&claims, "dom.admin") {
+        return e.into_response();
+    }
+
+    let subscriptions = state.subscriptions.list().await;
+    (StatusCode::OK, Json(subscriptions)).into_response()
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CloseSubscriptionRequest {
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReloadNodeKeysRequest {
+    /// Base64 Ed25519 public keys that replace the current trusted set.
+    pub keys: Vec<String>,
+}
+
+/// Force-close a runaway subscription (requires `dom.admin`).
+pub async fn admin_close_subscription(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Json(req_body): Json<CloseSubscriptionRequest>,
+) -> impl IntoResponse {
+    let claims = match validate_auth(&headers, &state).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    if let Err(e) = check_scope(&claims, "dom.admin") {
+        return e.into_response();
+    }
+
+    if state.subscriptions.close(&req_body.id).await {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (StatusCode::NOT_FOUND, "Unknown subscription").into_response()
+    }
+}
+
+/// Replace the set of node public keys trusted to sign writes, letting
+/// operators rotate node membership without restarting (requires `dom.admin`).
+pub async fn admin_reload_node_keys(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Json(req_body): Json<ReloadNodeKeysRequest>,
+) -> impl IntoResponse {
+    let claims = match validate_auth(&headers, &state).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    if let Err(e) = check_scope(&claims, "dom.admin") {
+        return e.into_response();
+    }
+
+    match state.node_auth.reload_encoded(&req_body.keys).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => {
+            error!("Failed to reload node keys: {}", e);
+            (StatusCode::BAD_REQUEST, "Invalid node key material").into_response()
+        }
+    }
+}
+
 /// App state for REST endpoints
 pub struct AppState {
     pub db: Arc<Database>,
     pub config: Config,
+    pub oidc: Arc<crate::oidc::JwksVerifier>,
+    pub subscriptions: crate::admin::SubscriptionRegistry,
+    pub node_auth: Arc<crate::security::NodeAuthenticator>,
 }
 
 /// Configure REST routes
@@ -288,8 +1001,23 @@ pub fn configure_routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/api/v1/diff", post(write_diff))
         .route("/api/v1/snapshot", get(read_snapshot))
+        .route("/api/v1/share", post(create_share))
         .route("/api/v1/history", get(get_history))
+        .route("/api/v1/batch/mutate", post(batch_mutate))
+        .route("/api/v1/batch/read", post(batch_read))
+        .route("/api/v1/admin/keys", get(list_api_keys).post(issue_api_key))
+        .route("/api/v1/admin/keys/revoke", post(revoke_api_key))
+        .route("/api/v1/admin/overview", get(admin_overview))
+        .route("/api/v1/admin/health", get(admin_health))
+        .route("/api/v1/admin/subscriptions", get(admin_list_subscriptions))
+        .route("/api/v1/admin/subscriptions/close", post(admin_close_subscription))
+        .route("/api/v1/admin/node-keys", post(admin_reload_node_keys))
         .route("/api/v1/health", get(health_check))
+        // Shrink the wire as well as the datastore: transparently inflate
+        // gzip/zstd request bodies (e.g. a compressed `write_diff`) and compress
+        // responses per the client's `Accept-Encoding` (snapshots, history).
+        .layer(tower_http::compression::CompressionLayer::new())
+        .layer(tower_http::decompression::RequestDecompressionLayer::new())
         .with_state(state)
 }
 
@@ -2,9 +2,12 @@ use crate::config::Config;
 use anyhow::Result;
 use rustls::ServerConfig;
 use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::BufReader;
 use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::RwLock;
 use tonic::transport::{Identity, ServerTlsConfig};
 
 /// Create TLS configuration with Kyber768 + X25519 hybrid
@@ -47,91 +50,777 @@ pub fn create_rustls_config(cert_path: &str, key_path: &str) -> Result<Arc<Serve
     Ok(Arc::new(config))
 }
 
-/// Validate JWT signature with post-quantum algorithms (placeholder)
-pub fn validate_pqc_signature(_token: &str, _public_key: &[u8]) -> Result<bool> {
-    // TODO: Implement Dilithium-3 signature verification
-    // For now, return true in development
-    Ok(true)
+/// Failure modes of a [`SignatureProvider`], kept distinct so callers can tell
+/// a genuinely bad signature from a token they simply cannot verify.
+#[derive(Debug, Error)]
+pub enum SignatureError {
+    /// The signature did not verify against the message and key.
+    #[error("signature verification failed")]
+    BadSignature,
+    /// The signature or public key was not well-formed for the algorithm.
+    #[error("malformed signature or key encoding")]
+    Malformed,
+    /// The requested algorithm is not compiled in / supported.
+    #[error("unsupported signature algorithm: {0}")]
+    Unsupported(String),
 }
 
-/// Encrypt field-level data with AES-256-GCM
-pub fn encrypt_field(data: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+/// Verifies a detached signature over a message with a public key.
+///
+/// Implementations are pluggable so the JWT path can be driven by a classical,
+/// post-quantum, or hybrid combiner without the caller knowing which.
+pub trait SignatureProvider: Send + Sync {
+    fn verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> std::result::Result<(), SignatureError>;
+}
+
+/// Classical Ed25519 verification (ring).
+pub struct Ed25519Provider;
+
+impl SignatureProvider for Ed25519Provider {
+    fn verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> std::result::Result<(), SignatureError> {
+        let peer =
+            ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, public_key);
+        peer.verify(message, signature)
+            .map_err(|_| SignatureError::BadSignature)
+    }
+}
+
+/// Post-quantum Dilithium-3 (ML-DSA-65) verification.
+pub struct Dilithium3Provider;
+
+impl SignatureProvider for Dilithium3Provider {
+    fn verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> std::result::Result<(), SignatureError> {
+        use pqcrypto_dilithium::dilithium3;
+        use pqcrypto_traits::sign::{DetachedSignature, PublicKey};
+
+        let pk = dilithium3::PublicKey::from_bytes(public_key)
+            .map_err(|_| SignatureError::Malformed)?;
+        let sig = dilithium3::DetachedSignature::from_bytes(signature)
+            .map_err(|_| SignatureError::Malformed)?;
+        dilithium3::verify_detached_signature(&sig, message, &pk)
+            .map_err(|_| SignatureError::BadSignature)
+    }
+}
+
+/// Conservative hybrid combiner: a classical and a post-quantum signature are
+/// carried side by side and BOTH must verify. The token stays secure as long
+/// as either scheme is unbroken.
+///
+/// Both the `signature` and `public_key` arguments are two length-prefixed
+/// halves — `len(classical):u32-be || classical || len(pqc):u32-be || pqc` —
+/// in classical-then-post-quantum order.
+pub struct HybridProvider {
+    classical: Box<dyn SignatureProvider>,
+    pqc: Box<dyn SignatureProvider>,
+}
+
+impl HybridProvider {
+    pub fn new(classical: Box<dyn SignatureProvider>, pqc: Box<dyn SignatureProvider>) -> Self {
+        Self { classical, pqc }
+    }
+
+    /// The shipped Ed25519 + Dilithium-3 combiner.
+    pub fn ed25519_dilithium3() -> Self {
+        Self::new(Box::new(Ed25519Provider), Box::new(Dilithium3Provider))
+    }
+}
+
+impl SignatureProvider for HybridProvider {
+    fn verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> std::result::Result<(), SignatureError> {
+        let (classical_sig, pqc_sig) = split_length_prefixed_pair(signature)?;
+        let (classical_pk, pqc_pk) = split_length_prefixed_pair(public_key)?;
+        self.classical.verify(message, classical_sig, classical_pk)?;
+        self.pqc.verify(message, pqc_sig, pqc_pk)?;
+        Ok(())
+    }
+}
+
+/// Split `len:u32-be || a || len:u32-be || b` into its two halves.
+fn split_length_prefixed_pair(buf: &[u8]) -> std::result::Result<(&[u8], &[u8]), SignatureError> {
+    fn take<'a>(buf: &'a [u8], at: usize) -> Option<(&'a [u8], usize)> {
+        let end = at.checked_add(4)?;
+        let len = u32::from_be_bytes(buf.get(at..end)?.try_into().ok()?) as usize;
+        let data_end = end.checked_add(len)?;
+        Some((buf.get(end..data_end)?, data_end))
+    }
+
+    let (first, next) = take(buf, 0).ok_or(SignatureError::Malformed)?;
+    let (second, end) = take(buf, next).ok_or(SignatureError::Malformed)?;
+    if end != buf.len() {
+        return Err(SignatureError::Malformed);
+    }
+    Ok((first, second))
+}
+
+/// Build the signature provider named by a config mode string.
+pub fn signature_provider(mode: &str) -> std::result::Result<Box<dyn SignatureProvider>, SignatureError> {
+    match mode {
+        "classical" => Ok(Box::new(Ed25519Provider)),
+        "pqc" => Ok(Box::new(Dilithium3Provider)),
+        "hybrid" => Ok(Box::new(HybridProvider::ed25519_dilithium3())),
+        other => Err(SignatureError::Unsupported(other.to_string())),
+    }
+}
+
+/// Envelope format version prefixed to every field-level ciphertext. Bumping
+/// this lets `decrypt_field` recognise and reject layouts it cannot parse.
+const FIELD_ENVELOPE_V1: u8 = 1;
+
+/// The nonce-collision ceiling for a single AES-256-GCM key: once a key has
+/// sealed this many records it is due for rotation regardless of age.
+const MAX_RECORDS_PER_KEY: u64 = 1 << 32;
+
+/// The logical location an encrypted field belongs to. The descriptor both
+/// steers HKDF subkey derivation and binds the ciphertext as AEAD associated
+/// data, so a blob sealed for one `(node_id, field_path)` cannot be relocated
+/// to another and still decrypt.
+pub struct FieldContext<'a> {
+    pub node_id: &'a str,
+    pub field_path: &'a str,
+}
+
+impl FieldContext<'_> {
+    /// The AEAD associated data bound to the ciphertext: `node_id || field_path`.
+    fn aad(&self) -> Vec<u8> {
+        let mut aad = Vec::with_capacity(self.node_id.len() + self.field_path.len());
+        aad.extend_from_slice(self.node_id.as_bytes());
+        aad.extend_from_slice(self.field_path.as_bytes());
+        aad
+    }
+
+    /// HKDF `info`: `node_id || 0x00 || field_path`. The separator keeps
+    /// `(a, bc)` and `(ab, c)` from colliding.
+    fn info(&self) -> Vec<u8> {
+        let mut info = Vec::with_capacity(self.node_id.len() + 1 + self.field_path.len());
+        info.extend_from_slice(self.node_id.as_bytes());
+        info.push(0x00);
+        info.extend_from_slice(self.field_path.as_bytes());
+        info
+    }
+}
+
+/// Derive a per-field AES-256 subkey from a root secret via
+/// `HKDF-Expand(HKDF-Extract(salt, root), info = node_id || 0x00 || field_path)`.
+/// Distinct fields get distinct subkeys, so a reused nonce across fields is
+/// harmless.
+fn derive_subkey(root: &[u8; 32], ctx: &FieldContext<'_>) -> [u8; 32] {
+    use ring::hkdf::{Salt, HKDF_SHA256};
+
+    let salt = Salt::new(HKDF_SHA256, b"virtual-dom-gateway/field-encryption");
+    let prk = salt.extract(root);
+    let info = ctx.info();
+    let okm = prk
+        .expand(&[&info], HKDF_SHA256)
+        .expect("HKDF expand of a 32-byte key never fails");
+    let mut subkey = [0u8; 32];
+    okm.fill(&mut subkey)
+        .expect("okm length matches the 32-byte subkey");
+    subkey
+}
+
+/// Seal `data` under a per-field subkey derived from `root`, with the field
+/// descriptor bound as associated data. Returns `nonce || ciphertext || tag`.
+fn seal_field(data: &[u8], root: &[u8; 32], ctx: &FieldContext<'_>) -> Result<Vec<u8>> {
     use ring::aead::{LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
     use ring::rand::{SecureRandom, SystemRandom};
-    
-    // Generate a random nonce
+
+    let subkey = derive_subkey(root, ctx);
     let rng = SystemRandom::new();
     let mut nonce_bytes = [0u8; 12];
     rng.fill(&mut nonce_bytes)
         .map_err(|_| anyhow::anyhow!("Failed to generate nonce"))?;
-    
-    // Create the key
-    let unbound_key = UnboundKey::new(&AES_256_GCM, key)
+
+    let unbound_key = UnboundKey::new(&AES_256_GCM, &subkey)
         .map_err(|_| anyhow::anyhow!("Invalid key length"))?;
     let less_safe_key = LessSafeKey::new(unbound_key);
-    
-    // Create nonce
     let nonce = Nonce::assume_unique_for_key(nonce_bytes);
-    
-    // Prepare ciphertext buffer and encrypt
+
     let mut ciphertext = data.to_vec();
-    less_safe_key.seal_in_place_append_tag(nonce, ring::aead::Aad::empty(), &mut ciphertext)
+    less_safe_key
+        .seal_in_place_append_tag(nonce, ring::aead::Aad::from(ctx.aad()), &mut ciphertext)
         .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
-    
-    // Combine nonce + ciphertext + tag
+
     let mut output = nonce_bytes.to_vec();
     output.extend_from_slice(&ciphertext);
-    
     Ok(output)
 }
 
-/// Decrypt field-level data
-pub fn decrypt_field(encrypted: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+/// Open a `nonce || ciphertext || tag` blob produced by [`seal_field`]. The
+/// descriptor must match the one used to seal or authentication fails.
+fn open_field(blob: &[u8], root: &[u8; 32], ctx: &FieldContext<'_>) -> Result<Vec<u8>> {
     use ring::aead::{LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
-    
-    // Ensure we have at least nonce + tag
-    if encrypted.len() < 12 + 16 {
+
+    if blob.len() < 12 + 16 {
         return Err(anyhow::anyhow!("Invalid encrypted data length"));
     }
-    
-    // Extract nonce from encrypted data
-    let (nonce_bytes, ciphertext) = encrypted.split_at(12);
+
+    let subkey = derive_subkey(root, ctx);
+    let (nonce_bytes, ciphertext) = blob.split_at(12);
     let mut nonce_array = [0u8; 12];
     nonce_array.copy_from_slice(nonce_bytes);
-    
-    // Create the key
-    let unbound_key = UnboundKey::new(&AES_256_GCM, key)
+
+    let unbound_key = UnboundKey::new(&AES_256_GCM, &subkey)
         .map_err(|_| anyhow::anyhow!("Invalid key length"))?;
     let less_safe_key = LessSafeKey::new(unbound_key);
-    
-    // Create nonce
     let nonce = Nonce::assume_unique_for_key(nonce_array);
-    
-    // Decrypt in place
+
     let mut decrypted = ciphertext.to_vec();
-    let plaintext_len = less_safe_key.open_in_place(nonce, ring::aead::Aad::empty(), &mut decrypted)
+    let plaintext_len = less_safe_key
+        .open_in_place(nonce, ring::aead::Aad::from(ctx.aad()), &mut decrypted)
         .map_err(|_| anyhow::anyhow!("Decryption failed"))?
         .len();
-    
-    // Truncate to actual plaintext length (removes tag)
+
     decrypted.truncate(plaintext_len);
-    
     Ok(decrypted)
 }
 
+/// A single data-encryption key identified by a small integer.
+struct DataKey {
+    material: [u8; 32],
+    created: std::time::Instant,
+    records: u64,
+}
+
+/// Mutable keyring state guarded by the ring's lock.
+struct KeyringState {
+    keys: std::collections::BTreeMap<u32, DataKey>,
+    current: u32,
+    next_id: u32,
+}
+
+/// An ordered set of data-encryption keys. One "current" key seals new data;
+/// older keys are retained for decryption until the retirement policy drops
+/// them. This lets a long-lived deployment re-key incrementally — promoting a
+/// fresh key periodically — instead of trusting one key forever or
+/// re-encrypting everything at once.
+///
+/// The wire layout is `version || key_id || nonce || ciphertext || tag`, so a
+/// stored ciphertext names the key that sealed it and decryption never has to
+/// guess.
+pub struct Keyring {
+    state: std::sync::RwLock<KeyringState>,
+    max_key_age: std::time::Duration,
+    retired_keys_retained: usize,
+}
+
+impl Keyring {
+    /// Build a keyring with a freshly generated current key (id 0).
+    pub fn new(max_key_age: std::time::Duration, retired_keys_retained: usize) -> Result<Self> {
+        let mut keys = std::collections::BTreeMap::new();
+        keys.insert(0, DataKey::generate()?);
+        Ok(Self {
+            state: std::sync::RwLock::new(KeyringState {
+                keys,
+                current: 0,
+                next_id: 1,
+            }),
+            max_key_age,
+            retired_keys_retained,
+        })
+    }
+
+    /// Build a keyring from configuration.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Self::new(
+            std::time::Duration::from_secs(config.key_rotation_secs),
+            config.retired_keys_retained,
+        )
+    }
+
+    /// Encrypt `data` under the current key for the given field location,
+    /// tagging the output with the envelope version and key id. The root for
+    /// HKDF derivation is the current key's material.
+    pub fn encrypt_field(&self, data: &[u8], ctx: &FieldContext<'_>) -> Result<Vec<u8>> {
+        let mut state = self.state.write().expect("keyring lock poisoned");
+        let current = state.current;
+        let sealed = {
+            let key = state
+                .keys
+                .get(&current)
+                .ok_or_else(|| anyhow::anyhow!("current key missing from keyring"))?;
+            seal_field(data, &key.material, ctx)?
+        };
+        if let Some(key) = state.keys.get_mut(&current) {
+            key.records = key.records.saturating_add(1);
+        }
+
+        let mut output = Vec::with_capacity(5 + sealed.len());
+        output.push(FIELD_ENVELOPE_V1);
+        output.extend_from_slice(&current.to_be_bytes());
+        output.extend_from_slice(&sealed);
+        Ok(output)
+    }
+
+    /// Decrypt a blob produced by [`Keyring::encrypt_field`], selecting the key
+    /// named in its header and failing cleanly on an unknown id or version.
+    /// The field context must match the one used to encrypt.
+    pub fn decrypt_field(&self, blob: &[u8], ctx: &FieldContext<'_>) -> Result<Vec<u8>> {
+        if blob.len() < 5 {
+            return Err(anyhow::anyhow!("Invalid encrypted data length"));
+        }
+        let version = blob[0];
+        if version != FIELD_ENVELOPE_V1 {
+            return Err(anyhow::anyhow!("unsupported envelope version {}", version));
+        }
+        let key_id = u32::from_be_bytes([blob[1], blob[2], blob[3], blob[4]]);
+
+        let state = self.state.read().expect("keyring lock poisoned");
+        let key = state
+            .keys
+            .get(&key_id)
+            .ok_or_else(|| anyhow::anyhow!("unknown key_id {}", key_id))?;
+        open_field(&blob[5..], &key.material, ctx)
+    }
+
+    /// Whether the current key has exceeded its age or record budget and should
+    /// be rotated to bound nonce-collision risk.
+    pub fn needs_rotation(&self) -> bool {
+        let state = self.state.read().expect("keyring lock poisoned");
+        match state.keys.get(&state.current) {
+            Some(key) => key.created.elapsed() >= self.max_key_age || key.records >= MAX_RECORDS_PER_KEY,
+            None => true,
+        }
+    }
+
+    /// Promote a freshly generated key to current, then drop retired keys
+    /// beyond the retention window. Returns the new current key id.
+    pub fn rotate(&self) -> Result<u32> {
+        let fresh = DataKey::generate()?;
+        let mut state = self.state.write().expect("keyring lock poisoned");
+        let id = state.next_id;
+        state.keys.insert(id, fresh);
+        state.current = id;
+        state.next_id += 1;
+
+        // Retain only the most recent `retired_keys_retained` non-current keys.
+        let mut retired: Vec<u32> = state.keys.keys().copied().filter(|k| *k != id).collect();
+        retired.sort_unstable();
+        if retired.len() > self.retired_keys_retained {
+            let drop_count = retired.len() - self.retired_keys_retained;
+            for old in retired.into_iter().take(drop_count) {
+                state.keys.remove(&old);
+            }
+        }
+        Ok(id)
+    }
+
+    /// Drive automatic rotation from a background task.
+    ///
+    /// Ticks at `check_interval`, promoting a fresh current key whenever
+    /// [`needs_rotation`](Keyring::needs_rotation) reports the active key has
+    /// exceeded its age or record budget. Bounding nonce-collision risk is only
+    /// useful if rotation actually fires without operator intervention, so the
+    /// running process owns this loop for the lifetime of the keyring.
+    pub fn spawn_rotation(self: Arc<Self>, check_interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(check_interval);
+            // The first tick fires immediately; skip it so a freshly generated
+            // key is never rotated out before it has done any work.
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if self.needs_rotation() {
+                    match self.rotate() {
+                        Ok(id) => tracing::info!("Rotated field-encryption key, new current id {}", id),
+                        Err(e) => tracing::error!("Key rotation failed: {}", e),
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl DataKey {
+    /// Generate a key with fresh random material.
+    fn generate() -> Result<Self> {
+        use ring::rand::{SecureRandom, SystemRandom};
+        let rng = SystemRandom::new();
+        let mut material = [0u8; 32];
+        rng.fill(&mut material)
+            .map_err(|_| anyhow::anyhow!("Failed to generate key material"))?;
+        Ok(Self {
+            material,
+            created: std::time::Instant::now(),
+            records: 0,
+        })
+    }
+}
+
+/// Metadata key naming the node that produced a write.
+pub const NODE_ID_META_KEY: &str = "_node_id";
+/// Metadata key carrying the base64 Ed25519 signature over the write tuple.
+pub const NODE_SIG_META_KEY: &str = "_node_sig";
+/// Metadata key carrying the base64 Ed25519 public key that signed the write.
+pub const NODE_KEY_META_KEY: &str = "_node_key";
+
+/// How per-node write authentication is configured.
+///
+/// Mirrors peer-to-peer VPN trust models: `Explicit` lists each peer's public
+/// key, while `SharedSecret` derives a single shared identity from a secret so
+/// every node computes the same keypair and trusts each other by construction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeAuthMode {
+    Disabled,
+    Explicit,
+    SharedSecret,
+}
+
+impl NodeAuthMode {
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "explicit" => NodeAuthMode::Explicit,
+            "shared_secret" | "shared-secret" => NodeAuthMode::SharedSecret,
+            _ => NodeAuthMode::Disabled,
+        }
+    }
+}
+
+/// Why a write's node signature was rejected.
+#[derive(Debug, Error)]
+pub enum NodeAuthError {
+    #[error("Write is not signed")]
+    MissingSignature,
+
+    #[error("Malformed node {0}")]
+    Malformed(&'static str),
+
+    #[error("Signing key is not in the trusted set")]
+    UntrustedKey,
+
+    #[error("Node signature does not verify")]
+    InvalidSignature,
+}
+
+/// Verifies that a write was signed by a node whose key the operator trusts.
+///
+/// The trusted set lives behind an `RwLock` so [`reload`](Self::reload) can
+/// rotate node membership without restarting the gateway.
+pub struct NodeAuthenticator {
+    mode: NodeAuthMode,
+    trusted: RwLock<HashSet<Vec<u8>>>,
+}
+
+impl NodeAuthenticator {
+    /// Build an authenticator from configuration, seeding the trusted set from
+    /// the configured keys (explicit mode) or the derived shared identity.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mode = NodeAuthMode::from_str(&config.node_auth_mode);
+        let mut trusted = HashSet::new();
+
+        match mode {
+            NodeAuthMode::Explicit => {
+                for encoded in &config.trusted_node_keys {
+                    trusted.insert(decode_key(encoded)?);
+                }
+            }
+            NodeAuthMode::SharedSecret => {
+                let secret = config.node_shared_secret.as_deref().ok_or_else(|| {
+                    anyhow::anyhow!("shared-secret node auth requires node_shared_secret")
+                })?;
+                let (_seed, public) = derive_identity(secret);
+                trusted.insert(public);
+            }
+            NodeAuthMode::Disabled => {}
+        }
+
+        Ok(Self {
+            mode,
+            trusted: RwLock::new(trusted),
+        })
+    }
+
+    /// Whether node write authentication is enforced at all.
+    pub fn is_enabled(&self) -> bool {
+        self.mode != NodeAuthMode::Disabled
+    }
+
+    /// Replace the trusted key set in place, so operators can add or remove
+    /// node members while the gateway is serving.
+    pub async fn reload(&self, keys: Vec<Vec<u8>>) {
+        let mut set = self.trusted.write().await;
+        *set = keys.into_iter().collect();
+    }
+
+    /// Replace the trusted set from base64-encoded public keys, decoding each
+    /// before swapping it in so a malformed entry aborts the reload untouched.
+    pub async fn reload_encoded(&self, encoded: &[String]) -> Result<()> {
+        let mut keys = Vec::with_capacity(encoded.len());
+        for key in encoded {
+            keys.push(decode_key(key)?);
+        }
+        self.reload(keys).await;
+        Ok(())
+    }
+
+    /// Verify the signature accompanying a write against the trusted key set.
+    ///
+    /// A disabled authenticator accepts every write. Otherwise the signature
+    /// and public key are read from the request `metadata`, the key must be in
+    /// the trusted set, and the Ed25519 signature must verify over
+    /// `(node_id || diff_bson || vector_clock)`.
+    pub async fn verify(
+        &self,
+        node_id: &str,
+        diff_bson: &[u8],
+        vector_clock: &[u8],
+        metadata: &HashMap<String, String>,
+    ) -> std::result::Result<(), NodeAuthError> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let signature = metadata
+            .get(NODE_SIG_META_KEY)
+            .ok_or(NodeAuthError::MissingSignature)
+            .and_then(|s| decode_key(s).map_err(|_| NodeAuthError::Malformed("signature")))?;
+        let public_key = metadata
+            .get(NODE_KEY_META_KEY)
+            .ok_or(NodeAuthError::MissingSignature)
+            .and_then(|s| decode_key(s).map_err(|_| NodeAuthError::Malformed("public key")))?;
+
+        {
+            let trusted = self.trusted.read().await;
+            if !trusted.contains(&public_key) {
+                return Err(NodeAuthError::UntrustedKey);
+            }
+        }
+
+        let message = signing_message(node_id, diff_bson, vector_clock);
+        let peer = ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, &public_key);
+        peer.verify(&message, &signature)
+            .map_err(|_| NodeAuthError::InvalidSignature)
+    }
+}
+
+/// The exact byte tuple a node signs: `node_id || diff_bson || vector_clock`.
+pub fn signing_message(node_id: &str, diff_bson: &[u8], vector_clock: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(node_id.len() + diff_bson.len() + vector_clock.len());
+    message.extend_from_slice(node_id.as_bytes());
+    message.extend_from_slice(diff_bson);
+    message.extend_from_slice(vector_clock);
+    message
+}
+
+/// Deterministically derive an Ed25519 identity (32-byte seed, public key) from
+/// a shared secret via HKDF so every node arrives at the same keypair.
+pub fn derive_identity(secret: &str) -> (Vec<u8>, Vec<u8>) {
+    use ring::hkdf::{Salt, HKDF_SHA256};
+
+    let salt = Salt::new(HKDF_SHA256, b"virtual-dom-gateway/node-identity");
+    let prk = salt.extract(secret.as_bytes());
+    let okm = prk
+        .expand(&[b"ed25519-seed"], HKDF_SHA256)
+        .expect("HKDF expand of a fixed length never fails");
+    let mut seed = [0u8; 32];
+    okm.fill(&mut seed)
+        .expect("okm length matches the 32-byte seed");
+
+    let keypair = ring::signature::Ed25519KeyPair::from_seed_unchecked(&seed)
+        .expect("a 32-byte seed is a valid Ed25519 seed");
+    let public = keypair.public_key().as_ref().to_vec();
+    (seed.to_vec(), public)
+}
+
+/// Decode a base64 node key or signature into raw bytes.
+fn decode_key(encoded: &str) -> Result<Vec<u8>> {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| anyhow::anyhow!("invalid base64 node key material: {}", e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn ctx<'a>(node_id: &'a str, field_path: &'a str) -> FieldContext<'a> {
+        FieldContext { node_id, field_path }
+    }
+
     #[test]
     fn test_field_encryption() {
-        let key = b"an example very very secret key."; // 32 bytes
+        let ring = Keyring::new(std::time::Duration::from_secs(3600), 2).unwrap();
         let plaintext = b"hello world";
-        
-        let encrypted = encrypt_field(plaintext, key).unwrap();
+        let c = ctx("node-x", "profile.email");
+
+        let encrypted = ring.encrypt_field(plaintext, &c).unwrap();
         assert_ne!(encrypted, plaintext);
-        
-        let decrypted = decrypt_field(&encrypted, key).unwrap();
+
+        let decrypted = ring.decrypt_field(&encrypted, &c).unwrap();
         assert_eq!(decrypted, plaintext);
     }
+
+    #[test]
+    fn test_field_encryption_rejects_relocated_ciphertext() {
+        let ring = Keyring::new(std::time::Duration::from_secs(3600), 2).unwrap();
+        let sealed = ring.encrypt_field(b"secret", &ctx("node-x", "field.a")).unwrap();
+
+        // Same key id, but a different logical location: AAD + subkey mismatch.
+        assert!(ring.decrypt_field(&sealed, &ctx("node-y", "field.a")).is_err());
+        assert!(ring.decrypt_field(&sealed, &ctx("node-x", "field.b")).is_err());
+    }
+
+    #[test]
+    fn test_keyring_rotation_decrypts_old_ciphertext() {
+        let ring = Keyring::new(std::time::Duration::from_secs(3600), 2).unwrap();
+        let c = ctx("node-x", "field.a");
+        let old = ring.encrypt_field(b"before rotation", &c).unwrap();
+
+        let new_id = ring.rotate().unwrap();
+        assert_eq!(new_id, 1);
+
+        // New data is sealed under the new key, old data still decrypts.
+        let new = ring.encrypt_field(b"after rotation", &c).unwrap();
+        assert_eq!(new[1..5], 1u32.to_be_bytes());
+        assert_eq!(old[1..5], 0u32.to_be_bytes());
+        assert_eq!(ring.decrypt_field(&old, &c).unwrap(), b"before rotation");
+        assert_eq!(ring.decrypt_field(&new, &c).unwrap(), b"after rotation");
+    }
+
+    #[test]
+    fn test_keyring_retires_old_keys() {
+        let ring = Keyring::new(std::time::Duration::from_secs(3600), 1).unwrap();
+        let c = ctx("node-x", "field.a");
+        let gen0 = ring.encrypt_field(b"gen0", &c).unwrap();
+        ring.rotate().unwrap(); // retains key 0
+        ring.rotate().unwrap(); // key 0 now beyond the retention window
+
+        let err = ring.decrypt_field(&gen0, &c).unwrap_err();
+        assert!(err.to_string().contains("unknown key_id"));
+    }
+
+    #[test]
+    fn test_keyring_rejects_unknown_version() {
+        let ring = Keyring::new(std::time::Duration::from_secs(3600), 2).unwrap();
+        let c = ctx("node-x", "field.a");
+        let mut blob = ring.encrypt_field(b"payload", &c).unwrap();
+        blob[0] = 0xff;
+        assert!(ring.decrypt_field(&blob, &c).unwrap_err().to_string().contains("envelope version"));
+    }
+
+    fn prefix_pair(a: &[u8], b: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(8 + a.len() + b.len());
+        out.extend_from_slice(&(a.len() as u32).to_be_bytes());
+        out.extend_from_slice(a);
+        out.extend_from_slice(&(b.len() as u32).to_be_bytes());
+        out.extend_from_slice(b);
+        out
+    }
+
+    #[test]
+    fn test_hybrid_signature_requires_both_halves() {
+        use pqcrypto_dilithium::dilithium3;
+        use pqcrypto_traits::sign::{DetachedSignature, PublicKey};
+        use ring::signature::{Ed25519KeyPair, KeyPair};
+
+        let message = b"header.payload";
+
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let ed = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let ed_sig = ed.sign(message);
+
+        let (dil_pk, dil_sk) = dilithium3::keypair();
+        let dil_sig = dilithium3::detached_sign(message, &dil_sk);
+
+        let sig = prefix_pair(ed_sig.as_ref(), dil_sig.as_bytes());
+        let pk = prefix_pair(ed.public_key().as_ref(), dil_pk.as_bytes());
+
+        let provider = HybridProvider::ed25519_dilithium3();
+        assert!(provider.verify(message, &sig, &pk).is_ok());
+
+        // Tampering the classical half is rejected.
+        let mut bad = ed_sig.as_ref().to_vec();
+        bad[0] ^= 0xff;
+        let relocated = prefix_pair(&bad, dil_sig.as_bytes());
+        assert!(matches!(
+            provider.verify(message, &relocated, &pk),
+            Err(SignatureError::BadSignature)
+        ));
+
+        // Tampering the post-quantum half is rejected.
+        let mut bad = dil_sig.as_bytes().to_vec();
+        bad[0] ^= 0xff;
+        let relocated = prefix_pair(ed_sig.as_ref(), &bad);
+        assert!(matches!(
+            provider.verify(message, &relocated, &pk),
+            Err(SignatureError::BadSignature)
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_signature_mode_is_typed_error() {
+        assert!(matches!(
+            signature_provider("rsa"),
+            Err(SignatureError::Unsupported(_))
+        ));
+    }
+
+    use base64::Engine;
+
+    fn b64(data: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(data)
+    }
+
+    fn signed_metadata(seed: &[u8], node_id: &str, diff: &[u8], clock: &[u8]) -> HashMap<String, String> {
+        let keypair = ring::signature::Ed25519KeyPair::from_seed_unchecked(seed).unwrap();
+        let sig = keypair.sign(&signing_message(node_id, diff, clock));
+        let mut meta = HashMap::new();
+        meta.insert(NODE_KEY_META_KEY.to_string(), b64(keypair.public_key().as_ref()));
+        meta.insert(NODE_SIG_META_KEY.to_string(), b64(sig.as_ref()));
+        meta
+    }
+
+    #[tokio::test]
+    async fn test_shared_secret_accepts_signed_write() {
+        let (seed, public) = derive_identity("peer-mesh-secret");
+        let auth = NodeAuthenticator {
+            mode: NodeAuthMode::SharedSecret,
+            trusted: RwLock::new(std::iter::once(public).collect()),
+        };
+
+        let meta = signed_metadata(&seed, "node-a", b"diff-bytes", &1i64.to_be_bytes());
+        assert!(auth.verify("node-a", b"diff-bytes", &1i64.to_be_bytes(), &meta).await.is_ok());
+
+        // A tampered payload no longer verifies.
+        assert!(matches!(
+            auth.verify("node-a", b"other", &1i64.to_be_bytes(), &meta).await,
+            Err(NodeAuthError::InvalidSignature)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_untrusted_key_rejected() {
+        let (seed, _public) = derive_identity("peer-mesh-secret");
+        let auth = NodeAuthenticator {
+            mode: NodeAuthMode::Explicit,
+            trusted: RwLock::new(HashSet::new()), // trusts nobody
+        };
+
+        let meta = signed_metadata(&seed, "node-a", b"diff", &1i64.to_be_bytes());
+        assert!(matches!(
+            auth.verify("node-a", b"diff", &1i64.to_be_bytes(), &meta).await,
+            Err(NodeAuthError::UntrustedKey)
+        ));
+    }
 }
\ No newline at end of file
@@ -14,38 +14,167 @@ use tracing::{debug, error, info, instrument};
 pub struct MemoryGatewayService {
     db: Arc<Database>,
     config: Config,
+    oidc: Arc<crate::oidc::JwksVerifier>,
+    subscriptions: crate::admin::SubscriptionRegistry,
+    node_auth: Arc<crate::security::NodeAuthenticator>,
 }
 
 impl MemoryGatewayService {
-    pub fn new(client: Client, config: Config) -> Self {
+    pub fn new(
+        client: Client,
+        config: Config,
+        subscriptions: crate::admin::SubscriptionRegistry,
+        node_auth: Arc<crate::security::NodeAuthenticator>,
+    ) -> Self {
+        let oidc = Arc::new(crate::oidc::JwksVerifier::new(&config));
+        let db = Database::new(client)
+            .with_compression(
+                crate::compression::Codec::from_str(&config.compression_codec),
+                config.compression_threshold,
+            )
+            .with_max_diff_bytes(config.max_diff_bytes);
         Self {
-            db: Arc::new(Database::new(client)),
+            db: Arc::new(db),
             config,
+            oidc,
+            subscriptions,
+            node_auth,
         }
     }
 
-    /// Extract and validate JWT claims from request
-    fn validate_auth(&self, request: &Request<impl std::fmt::Debug>) -> Result<Claims, Status> {
-        let token = request
+    /// Verify a write's node signature, mapping a rejection to a `Status` that
+    /// distinguishes an untrusted key from a bad or missing signature.
+    async fn verify_node_write(
+        &self,
+        node_id: &str,
+        diff_bson: &[u8],
+        parent_version: i64,
+        metadata: &std::collections::HashMap<String, String>,
+    ) -> Result<(), Status> {
+        use crate::security::NodeAuthError;
+
+        self.node_auth
+            .verify(node_id, diff_bson, &parent_version.to_be_bytes(), metadata)
+            .await
+            .map_err(|e| match e {
+                NodeAuthError::UntrustedKey => {
+                    Status::permission_denied("Write signed by an untrusted node key")
+                }
+                other => Status::unauthenticated(format!("Node authentication failed: {}", other)),
+            })
+    }
+
+    /// Extract and validate credentials from a request.
+    ///
+    /// Accepts either a `Bearer <jwt>` (verified by a trusted OIDC issuer, the
+    /// configured asymmetric `jwt_public_key`, or the static secret) or an
+    /// `ApiKey <key>` opaque credential resolved against the MongoDB key store.
+    async fn validate_auth(
+        &self,
+        request: &Request<impl std::fmt::Debug>,
+    ) -> Result<Claims, Status> {
+        let header = request
             .metadata()
             .get("authorization")
             .and_then(|v| v.to_str().ok())
-            .and_then(|v| v.strip_prefix("Bearer "))
             .ok_or_else(|| Status::unauthenticated("Missing authorization header"))?;
 
-        crate::auth::validate_token(token, &self.config.jwt_secret)
-            .map_err(|e| Status::unauthenticated(format!("Invalid token: {}", e)))
+        if let Some(token) = header.strip_prefix("Bearer ") {
+            // Federate to an external OIDC provider when the token's issuer is
+            // trusted; otherwise fall back to the local verifiers below.
+            if self.oidc.is_configured() {
+                if let Ok(iss) = crate::oidc::unverified_issuer(token) {
+                    if self.oidc.knows_issuer(&iss) {
+                        return self
+                            .oidc
+                            .verify(token)
+                            .await
+                            .map_err(|e| Status::unauthenticated(format!("Invalid token: {}", e)));
+                    }
+                }
+            }
+
+            // When a verifying key is configured, tokens are checked with the
+            // asymmetric scheme named by `jwt_signature_mode`; otherwise fall
+            // back to the symmetric secret.
+            if let Some(public_key_b64) = &self.config.jwt_public_key {
+                use base64::Engine;
+                let public_key = base64::engine::general_purpose::STANDARD
+                    .decode(public_key_b64.trim())
+                    .map_err(|_| Status::unauthenticated("Invalid configured JWT public key"))?;
+                return crate::auth::validate_token_signed(
+                    token,
+                    &self.config.jwt_signature_mode,
+                    &public_key,
+                )
+                .map_err(|e| Status::unauthenticated(format!("Invalid token: {}", e)));
+            }
+
+            return crate::auth::validate_token(token, &self.config.jwt_secret)
+                .map_err(|e| Status::unauthenticated(format!("Invalid token: {}", e)));
+        }
+
+        if let Some(key) = header.strip_prefix("ApiKey ") {
+            return self.validate_api_key(key).await;
+        }
+
+        Err(Status::unauthenticated("Unsupported authorization scheme"))
     }
 
-    /// Check if user has required scope
-    fn check_scope(&self, claims: &Claims, required: &str) -> Result<(), Status> {
-        if !claims.scopes.contains(&required.to_string()) {
-            return Err(Status::permission_denied(format!(
-                "Missing required scope: {}",
-                required
-            )));
+    /// Resolve an opaque API key to claims, rejecting it with a distinct reason
+    /// when it is unknown, revoked, or outside its validity window.
+    async fn validate_api_key(&self, key: &str) -> Result<Claims, Status> {
+        use crate::auth::KeyStatus;
+
+        let stored = self
+            .db
+            .find_api_key(key)
+            .await
+            .map_err(|e| Status::internal(format!("Key lookup failed: {}", e)))?;
+
+        let now = chrono::Utc::now().timestamp();
+        match crate::auth::check_key_validity(stored.as_ref(), now) {
+            KeyStatus::Valid => Ok(stored.unwrap().to_claims()),
+            KeyStatus::NotYetValid => Err(Status::unauthenticated("API key not yet valid")),
+            KeyStatus::Expired => Err(Status::unauthenticated("API key expired")),
+            KeyStatus::Revoked => Err(Status::unauthenticated("API key revoked")),
+            KeyStatus::Unknown => Err(Status::unauthenticated("Unknown API key")),
+        }
+    }
+
+    /// Authorize a request against the caller's path-scoped RBAC roles.
+    ///
+    /// Loads the caller's roles, expands them (along with any legacy flat
+    /// scopes) into grants, and permits the request only if some grant covers
+    /// the target repo/branch/path with sufficient access. Otherwise returns
+    /// `permission_denied` naming the resource that no grant covered.
+    async fn authorize(
+        &self,
+        claims: &Claims,
+        repo: &str,
+        branch: &str,
+        path: &str,
+        access: crate::auth::Access,
+    ) -> Result<(), Status> {
+        let roles = self
+            .db
+            .load_roles(&claims.roles)
+            .await
+            .map_err(|e| Status::internal(format!("Role lookup failed: {}", e)))?;
+
+        let mut grants = crate::auth::legacy_scope_grants(&claims.scopes);
+        for role in roles {
+            grants.extend(role.grants);
+        }
+
+        if crate::auth::grants_cover(&grants, repo, branch, path, access) {
+            Ok(())
+        } else {
+            Err(Status::permission_denied(format!(
+                "No role grants {:?} access to {}/{}/{}",
+                access, repo, branch, path
+            )))
         }
-        Ok(())
     }
 }
 
@@ -57,11 +186,22 @@ impl MemoryGateway for MemoryGatewayService {
         request: Request<WriteDiffRequest>,
     ) -> Result<Response<WriteDiffResponse>, Status> {
         // Validate authentication
-        let claims = self.validate_auth(&request)?;
-        self.check_scope(&claims, "dom.write")?;
+        let claims = self.validate_auth(&request).await?;
 
         let req = request.into_inner();
-        metrics::WRITE_REQUESTS.inc();
+        self.authorize(&claims, &req.repo, &req.branch, &req.path, crate::auth::Access::Write)
+            .await?;
+
+        // Prove the originating node produced this diff before accepting it.
+        let node_id = req
+            .metadata
+            .get(crate::security::NODE_ID_META_KEY)
+            .cloned()
+            .unwrap_or_else(|| claims.sub.clone());
+        self.verify_node_write(&node_id, &req.diff, req.parent_version, &req.metadata)
+            .await?;
+
+        let _timer = metrics::start_timer("write_diff", &req.repo);
 
         // Create document
         let doc = Document {
@@ -80,7 +220,7 @@ impl MemoryGateway for MemoryGatewayService {
         // Attempt to write with optimistic locking
         match self.db.write_document(doc, req.parent_version).await {
             Ok((id, version)) => {
-                metrics::WRITE_SUCCESS.inc();
+                metrics::record_write(&req.repo, &req.branch, "success");
                 Ok(Response::new(WriteDiffResponse {
                     id,
                     version,
@@ -89,8 +229,8 @@ impl MemoryGateway for MemoryGatewayService {
                 }))
             }
             Err(ServiceError::VersionConflict { current }) => {
-                metrics::WRITE_CONFLICTS.inc();
-                
+                metrics::record_write(&req.repo, &req.branch, "conflict");
+
                 // Fetch current content for conflict info
                 let current_doc = self
                     .db
@@ -111,7 +251,7 @@ impl MemoryGateway for MemoryGatewayService {
                 }))
             }
             Err(e) => {
-                metrics::WRITE_ERRORS.inc();
+                metrics::record_write(&req.repo, &req.branch, "error");
                 error!("Write failed: {}", e);
                 Err(Status::internal("Write operation failed"))
             }
@@ -124,11 +264,12 @@ impl MemoryGateway for MemoryGatewayService {
         request: Request<ReadSnapshotRequest>,
     ) -> Result<Response<ReadSnapshotResponse>, Status> {
         // Validate authentication
-        let claims = self.validate_auth(&request)?;
-        self.check_scope(&claims, "dom.read")?;
+        let claims = self.validate_auth(&request).await?;
 
         let req = request.into_inner();
-        metrics::READ_REQUESTS.inc();
+        self.authorize(&claims, &req.repo, &req.branch, &req.path, crate::auth::Access::Read)
+            .await?;
+        let _timer = metrics::start_timer("read_snapshot", &req.repo);
 
         match self
             .db
@@ -136,7 +277,7 @@ impl MemoryGateway for MemoryGatewayService {
             .await
         {
             Ok(doc) => {
-                metrics::READ_SUCCESS.inc();
+                metrics::record_read(&req.repo, &req.branch, "success");
                 Ok(Response::new(ReadSnapshotResponse {
                     id: doc.id.unwrap_or_default(),
                     content: doc.blob,
@@ -147,11 +288,11 @@ impl MemoryGateway for MemoryGatewayService {
                 }))
             }
             Err(ServiceError::NotFound) => {
-                metrics::READ_NOT_FOUND.inc();
+                metrics::record_read(&req.repo, &req.branch, "not_found");
                 Err(Status::not_found("Document not found"))
             }
             Err(e) => {
-                metrics::READ_ERRORS.inc();
+                metrics::record_read(&req.repo, &req.branch, "error");
                 error!("Read failed: {}", e);
                 Err(Status::internal("Read operation failed"))
             }
@@ -166,29 +307,64 @@ impl MemoryGateway for MemoryGatewayService {
         request: Request<SubscribeChangesRequest>,
     ) -> Result<Response<Self::SubscribeChangesStream>, Status> {
         // Validate authentication
-        let claims = self.validate_auth(&request)?;
-        self.check_scope(&claims, "dom.read")?;
+        let claims = self.validate_auth(&request).await?;
 
         let req = request.into_inner();
+        // A subscription can watch several paths; every one must be covered. An
+        // empty path list means the whole branch, authorized at its root.
+        if req.paths.is_empty() {
+            self.authorize(&claims, &req.repo, &req.branch, "", crate::auth::Access::Read)
+                .await?;
+        } else {
+            for path in &req.paths {
+                self.authorize(&claims, &req.repo, &req.branch, path, crate::auth::Access::Read)
+                    .await?;
+            }
+        }
+
         let (tx, rx) = tokio::sync::mpsc::channel(128);
 
-        // Spawn task to watch changes
+        // Register the subscription so the admin API and the gauge see the same
+        // live set. The gauge is bumped here and decremented once the watch task
+        // finishes (client drop, error, or admin force-close).
+        let id = self.subscriptions.allocate_id();
+        let info = crate::admin::SubscriptionInfo {
+            id: id.clone(),
+            subscriber: claims.sub.clone(),
+            repo: req.repo.clone(),
+            branch: req.branch.clone(),
+            paths: req.paths.clone(),
+            since: chrono::Utc::now(),
+        };
+
         let db = self.db.clone();
-        tokio::spawn(async move {
+        let registry = self.subscriptions.clone();
+        metrics::ACTIVE_SUBSCRIPTIONS.inc();
+        let handle = tokio::spawn(async move {
+            let resume_token = if req.resume_token.is_empty() {
+                None
+            } else {
+                Some(req.resume_token)
+            };
             if let Err(e) = db
                 .watch_changes(
                     &req.repo,
                     &req.branch,
                     req.paths,
                     req.from_version as u64,
+                    resume_token,
                     tx,
                 )
                 .await
             {
                 error!("Change stream error: {}", e);
             }
+            registry.deregister(&id).await;
+            metrics::ACTIVE_SUBSCRIPTIONS.dec();
         });
 
+        self.subscriptions.register(info, handle.abort_handle()).await;
+
         Ok(Response::new(tokio_stream::wrappers::ReceiverStream::new(rx)))
     }
 
@@ -198,11 +374,13 @@ impl MemoryGateway for MemoryGatewayService {
         request: Request<ResolveConflictRequest>,
     ) -> Result<Response<ResolveConflictResponse>, Status> {
         // Validate authentication
-        let claims = self.validate_auth(&request)?;
-        self.check_scope(&claims, "dom.write")?;
+        let claims = self.validate_auth(&request).await?;
 
         let req = request.into_inner();
-        
+        self.authorize(&claims, &req.repo, &req.branch, &req.path, crate::auth::Access::Write)
+            .await?;
+        let _timer = metrics::start_timer("resolve_conflict", &req.repo);
+
         // Use conflict resolver based on strategy
         let merged_content = match req.strategy.as_str() {
             "ours" => req.local_content,
@@ -224,17 +402,221 @@ impl MemoryGateway for MemoryGatewayService {
         }))
     }
 
+    #[instrument(skip(self, request))]
+    async fn batch_mutate(
+        &self,
+        request: Request<BatchMutateRequest>,
+    ) -> Result<Response<BatchMutateResponse>, Status> {
+        // Validate authentication
+        let claims = self.validate_auth(&request).await?;
+
+        let req = request.into_inner();
+
+        // Apply each write independently so one failed, forbidden, or
+        // conflicted item doesn't abort the rest of the batch.
+        let mut results = Vec::with_capacity(req.items.len());
+        for item in req.items {
+            let _timer = metrics::start_timer("batch_mutate", &item.repo);
+
+            // An authorization failure is reported against its own item rather
+            // than aborting the batch, so earlier committed writes stay visible.
+            if let Err(status) = self
+                .authorize(&claims, &item.repo, &item.branch, &item.path, crate::auth::Access::Write)
+                .await
+            {
+                metrics::record_write(&item.repo, &item.branch, "forbidden");
+                results.push(BatchMutateResult {
+                    id: String::new(),
+                    version: 0,
+                    conflict: None,
+                    error: status.message().to_string(),
+                });
+                continue;
+            }
+
+            let node_id = item
+                .metadata
+                .get(crate::security::NODE_ID_META_KEY)
+                .cloned()
+                .unwrap_or_else(|| claims.sub.clone());
+            if let Err(status) = self
+                .verify_node_write(&node_id, &item.diff, item.parent_version, &item.metadata)
+                .await
+            {
+                metrics::record_write(&item.repo, &item.branch, "error");
+                results.push(BatchMutateResult {
+                    id: String::new(),
+                    version: 0,
+                    conflict: None,
+                    error: status.message().to_string(),
+                });
+                continue;
+            }
+
+            let doc = Document {
+                id: None,
+                repo: item.repo.clone(),
+                branch: item.branch.clone(),
+                path: item.path.clone(),
+                blob: item.diff,
+                author: claims.sub.clone(),
+                version: VectorClock::new(),
+                timestamp: chrono::Utc::now(),
+                doc_type: "diff".to_string(),
+                metadata: item.metadata,
+            };
+
+            let result = match self.db.write_document(doc, item.parent_version).await {
+                Ok((id, version)) => {
+                    metrics::record_write(&item.repo, &item.branch, "success");
+                    BatchMutateResult {
+                        id,
+                        version,
+                        conflict: None,
+                        error: String::new(),
+                    }
+                }
+                Err(ServiceError::VersionConflict { current }) => {
+                    metrics::record_write(&item.repo, &item.branch, "conflict");
+
+                    match self
+                        .db
+                        .read_document(&item.repo, &item.branch, &item.path, Some(current))
+                        .await
+                    {
+                        Ok(current_doc) => BatchMutateResult {
+                            id: String::new(),
+                            version: 0,
+                            conflict: Some(ConflictInfo {
+                                has_conflict: true,
+                                current_version: current,
+                                current_author: current_doc.author,
+                                current_content: current_doc.blob,
+                            }),
+                            error: String::new(),
+                        },
+                        Err(e) => BatchMutateResult {
+                            id: String::new(),
+                            version: 0,
+                            conflict: None,
+                            error: format!("Failed to fetch conflict info: {}", e),
+                        },
+                    }
+                }
+                Err(e) => {
+                    metrics::record_write(&item.repo, &item.branch, "error");
+                    error!("Batch write failed: {}", e);
+                    BatchMutateResult {
+                        id: String::new(),
+                        version: 0,
+                        conflict: None,
+                        error: "Write operation failed".to_string(),
+                    }
+                }
+            };
+
+            results.push(result);
+        }
+
+        Ok(Response::new(BatchMutateResponse { results }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn batch_read(
+        &self,
+        request: Request<BatchReadRequest>,
+    ) -> Result<Response<BatchReadResponse>, Status> {
+        // Validate authentication
+        let claims = self.validate_auth(&request).await?;
+
+        let req = request.into_inner();
+
+        let mut results = Vec::with_capacity(req.items.len());
+        for item in req.items {
+            let _timer = metrics::start_timer("batch_read", &item.repo);
+
+            // Report a denied item in place so the rest of the batch still
+            // returns its snapshots.
+            if let Err(status) = self
+                .authorize(&claims, &item.repo, &item.branch, &item.path, crate::auth::Access::Read)
+                .await
+            {
+                metrics::record_read(&item.repo, &item.branch, "forbidden");
+                results.push(BatchReadResult {
+                    found: false,
+                    id: String::new(),
+                    content: Vec::new(),
+                    version: 0,
+                    author: String::new(),
+                    metadata: Default::default(),
+                    error: status.message().to_string(),
+                });
+                continue;
+            }
+
+            let result = match self
+                .db
+                .read_document(&item.repo, &item.branch, &item.path, item.version.into())
+                .await
+            {
+                Ok(doc) => {
+                    metrics::record_read(&item.repo, &item.branch, "success");
+                    BatchReadResult {
+                        found: true,
+                        id: doc.id.unwrap_or_default(),
+                        content: doc.blob,
+                        version: doc.version.value(),
+                        author: doc.author,
+                        metadata: doc.metadata,
+                        error: String::new(),
+                    }
+                }
+                Err(ServiceError::NotFound) => {
+                    metrics::record_read(&item.repo, &item.branch, "not_found");
+                    BatchReadResult {
+                        found: false,
+                        id: String::new(),
+                        content: Vec::new(),
+                        version: 0,
+                        author: String::new(),
+                        metadata: Default::default(),
+                        error: String::new(),
+                    }
+                }
+                Err(e) => {
+                    metrics::record_read(&item.repo, &item.branch, "error");
+                    error!("Batch read failed: {}", e);
+                    BatchReadResult {
+                        found: false,
+                        id: String::new(),
+                        content: Vec::new(),
+                        version: 0,
+                        author: String::new(),
+                        metadata: Default::default(),
+                        error: "Read operation failed".to_string(),
+                    }
+                }
+            };
+
+            results.push(result);
+        }
+
+        Ok(Response::new(BatchReadResponse { results }))
+    }
+
     #[instrument(skip(self, request))]
     async fn get_history(
         &self,
         request: Request<GetHistoryRequest>,
     ) -> Result<Response<GetHistoryResponse>, Status> {
         // Validate authentication
-        let claims = self.validate_auth(&request)?;
-        self.check_scope(&claims, "dom.read")?;
+        let claims = self.validate_auth(&request).await?;
 
         let req = request.into_inner();
-        
+        self.authorize(&claims, &req.repo, &req.branch, &req.path, crate::auth::Access::Read)
+            .await?;
+        let _timer = metrics::start_timer("get_history", &req.repo);
+
         match self
             .db
             .get_history(